@@ -0,0 +1,80 @@
+//! Steps per second of a game at various snake lengths.
+//!
+//! Run with `cargo bench --bench step`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ::snake::{Direction, Game, Level, Point, Rules, Snake};
+
+const LENGTHS: [usize; 4] = [10, 100, 1_000, 10_000];
+const DURATION: Duration = Duration::from_millis(500);
+
+/// Steps `step` as many times as possible for [`DURATION`], returning the
+/// rate.
+fn steps_per_second(mut step: impl FnMut()) -> f64 {
+    let start = Instant::now();
+    let mut steps = 0_u64;
+
+    while start.elapsed() < DURATION {
+        for _ in 0..1_000 {
+            step();
+        }
+        steps += 1_000;
+    }
+
+    steps as f64 / start.elapsed().as_secs_f64()
+}
+
+/// Snake of `length` cells going straight along a wrapping row twice as
+/// long, so that it never runs into itself.
+fn snake(length: usize) -> Snake {
+    let width = length * 2;
+    let mut snake = Snake::new(
+        Point::new(length as isize - 1, 0),
+        length - 1,
+        Direction::Right,
+        width,
+        1,
+    );
+    snake.set_wrap(Some((width, 1)));
+    snake
+}
+
+/// Game on the same row as [`snake`], with the apple on a row of its own
+/// out of the way.
+fn game(length: usize) -> Game {
+    let mut level = Level::empty(length * 2, 2);
+    level.head = Point::new(length as isize - 1, 0);
+    level.length = length;
+    level.apples = vec![Point::new(0, 1)];
+
+    let mut game = Game::from_level(level, 0);
+    game.set_rules(Rules { wrap_around: true });
+    game
+}
+
+fn main() {
+    println!(
+        "{:>8}  {:>14}  {:>14}",
+        "length", "snake steps/s", "game steps/s"
+    );
+
+    for length in LENGTHS {
+        let mut snake = snake(length);
+        let snake_rate = steps_per_second(|| {
+            snake.step();
+            black_box(&snake);
+        });
+        assert!(!snake.is_dead());
+
+        let mut game = game(length);
+        let game_rate = steps_per_second(|| {
+            game.step();
+            black_box(&game);
+        });
+        assert!(game.is_running());
+
+        println!("{length:>8}  {snake_rate:>14.0}  {game_rate:>14.0}");
+    }
+}
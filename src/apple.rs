@@ -6,6 +6,7 @@ use ratatui::{
 
 use crate::point::Point;
 
+/// An apple the snake can eat to grow.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Apple(Point);
 
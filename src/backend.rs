@@ -0,0 +1,61 @@
+use std::str::FromStr;
+
+#[cfg(not(any(feature = "wgpu", feature = "ndarray")))]
+compile_error!("the `rl` feature needs a training backend, enable `wgpu` or `ndarray`");
+
+/// Burn backend the agent is trained on, among the ones enabled at compile
+/// time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// GPU through wgpu.
+    #[cfg(feature = "wgpu")]
+    Wgpu,
+    /// CPU through ndarray.
+    #[cfg(feature = "ndarray")]
+    NdArray,
+}
+
+impl Backend {
+    pub const VARIANTS: &[Backend] = &[
+        #[cfg(feature = "wgpu")]
+        Backend::Wgpu,
+        #[cfg(feature = "ndarray")]
+        Backend::NdArray,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "wgpu")]
+            Backend::Wgpu => "wgpu",
+            #[cfg(feature = "ndarray")]
+            Backend::NdArray => "ndarray",
+        }
+    }
+}
+
+impl Default for Backend {
+    fn default() -> Self {
+        Self::VARIANTS[0]
+    }
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::VARIANTS
+            .iter()
+            .copied()
+            .find(|backend| backend.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::VARIANTS
+                    .iter()
+                    .map(|backend| backend.name())
+                    .collect();
+                format!(
+                    "unknown backend '{s}', expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
@@ -0,0 +1,309 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use rl::burn::{
+    prelude::{Backend, Config, Module},
+    record::{FullPrecisionSettings, NamedMpkFileRecorder},
+};
+
+use snake::Level;
+
+use crate::cli::Args;
+use crate::model::{LinearQNet, LinearQNetConfig};
+
+/// Checkpoints of a training run, kept in a directory:
+///
+/// - `model.json`, the [`LinearQNetConfig`] of the network,
+/// - `latest.mpk`, the weights of the last checkpoint,
+/// - `best.mpk`, the weights that scored best on evaluation,
+/// - `progress`, the [`Progress`] of the run,
+/// - `settings`, the [`settings`] of the agent and of the environment, which
+///   resuming must not change.
+#[derive(Debug, Clone)]
+pub struct Checkpoints {
+    dir: PathBuf,
+    settings: String,
+}
+
+/// How far a training run went.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// Episodes trained so far.
+    pub episode: usize,
+    /// Best average evaluation score so far.
+    pub best_score: Option<f64>,
+    /// Steps taken so far.
+    pub steps: u64,
+    /// Time spent training so far.
+    pub elapsed: Duration,
+}
+
+impl Checkpoints {
+    /// Checkpoints in `dir` of a run trained with `settings`.
+    pub fn new(dir: impl Into<PathBuf>, settings: String) -> Self {
+        Self {
+            dir: dir.into(),
+            settings,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Saves `model` as the latest checkpoint, and as the best one if it
+    /// scored `score`, better than any before.
+    pub fn save<B: Backend>(
+        &self,
+        model: &LinearQNet<B>,
+        config: &LinearQNetConfig,
+        progress: &mut Progress,
+        score: f64,
+    ) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        fs::write(self.dir.join("settings"), &self.settings)?;
+        config.save(self.dir.join("model.json"))?;
+        save_weights(model, self.dir.join("latest"))?;
+
+        if progress.best_score.is_none_or(|best| score > best) {
+            save_weights(model, self.dir.join("best"))?;
+            progress.best_score = Some(score);
+        }
+
+        fs::write(self.dir.join("progress"), progress.to_string())
+    }
+
+    /// Loads the latest checkpoint to resume training from it.
+    ///
+    /// Fails if it was trained with other settings.
+    pub fn load<B: Backend>(
+        &self,
+        device: &B::Device,
+    ) -> io::Result<(LinearQNet<B>, LinearQNetConfig, Progress)> {
+        let settings = fs::read_to_string(self.dir.join("settings"))?;
+        check_settings(&settings, &self.settings)?;
+
+        let config = LinearQNetConfig::load(self.dir.join("model.json"))
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        let model = load_weights(&config, self.dir.join("latest"), device)?;
+        let progress = fs::read_to_string(self.dir.join("progress"))?
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok((model, config, progress))
+    }
+}
+
+/// Settings of a training run that shape what the model learns: the
+/// configuration of the agent, the board, the actions, the observation, the
+/// rewards and the episode limits.
+pub fn settings(args: &Args, agent: &impl fmt::Debug) -> String {
+    let optional = |value: Option<usize>| value.map_or(String::from("none"), |v| v.to_string());
+
+    let mut settings = format!(
+        "agent {agent:?}\n\
+         board {}x{}\n\
+         wrap {}\n\
+         actions {}\n\
+         observation {}\n\
+         max-steps-without-eating {}\n\
+         max-steps {}\n",
+        args.width,
+        args.height,
+        args.wrap,
+        args.actions,
+        args.observation,
+        optional(args.limits.max_steps_without_eating),
+        optional(args.limits.max_steps),
+    );
+    // The whole level, as levels may share a name
+    let level = match &args.level {
+        Some(level) => level.to_string(),
+        None => Level::empty(args.width, args.height).to_string(),
+    };
+    for line in level.lines() {
+        settings.push_str(&format!("level {line}\n"));
+    }
+    for line in args.rewards.to_string().lines() {
+        settings.push_str(&format!("reward-{line}\n"));
+    }
+    settings
+}
+
+/// Fails on the first setting that differs between `saved` and `current`.
+fn check_settings(saved: &str, current: &str) -> io::Result<()> {
+    let mut saved = saved.lines();
+    let mut current = current.lines();
+
+    loop {
+        match (saved.next(), current.next()) {
+            (None, None) => return Ok(()),
+            (saved, current) if saved == current => (),
+            (saved, current) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "checkpoint was trained with '{}', not '{}'",
+                        saved.unwrap_or_default(),
+                        current.unwrap_or_default(),
+                    ),
+                ))
+            }
+        }
+    }
+}
+
+/// Loads the weights saved at `path`, with or without extension, of a
+/// network described by the `model.json` next to them.
+#[cfg(feature = "tui")]
+pub fn load_model<B: Backend>(path: &Path, device: &B::Device) -> io::Result<LinearQNet<B>> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let config = LinearQNetConfig::load(dir.join("model.json"))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+
+    load_weights(&config, path.with_extension(""), device)
+}
+
+fn save_weights<B: Backend>(model: &LinearQNet<B>, path: PathBuf) -> io::Result<()> {
+    let recorder = NamedMpkFileRecorder::<FullPrecisionSettings>::new();
+    model
+        .clone()
+        .save_file(path, &recorder)
+        .map_err(|err| io::Error::other(err.to_string()))
+}
+
+fn load_weights<B: Backend>(
+    config: &LinearQNetConfig,
+    path: PathBuf,
+    device: &B::Device,
+) -> io::Result<LinearQNet<B>> {
+    let recorder = NamedMpkFileRecorder::<FullPrecisionSettings>::new();
+    config
+        .init(device)
+        .load_file(path, &recorder, device)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "episode {}", self.episode)?;
+        if let Some(best_score) = self.best_score {
+            writeln!(f, "best-score {best_score}")?;
+        }
+        writeln!(f, "steps {}", self.steps)?;
+        writeln!(f, "elapsed {}", self.elapsed.as_secs_f64())
+    }
+}
+
+impl FromStr for Progress {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut progress = Self::default();
+
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let invalid = || format!("invalid {key} '{value}'");
+            match key {
+                "episode" => progress.episode = value.parse().map_err(|_| invalid())?,
+                "best-score" => progress.best_score = Some(value.parse().map_err(|_| invalid())?),
+                "steps" => progress.steps = value.parse().map_err(|_| invalid())?,
+                "elapsed" => {
+                    progress.elapsed = value
+                        .parse()
+                        .ok()
+                        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                        .ok_or_else(invalid)?
+                }
+                _ => return Err(format!("unknown field '{key}'")),
+            }
+        }
+
+        Ok(progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_round_trips() {
+        for progress in [
+            Progress::default(),
+            Progress {
+                episode: 48,
+                best_score: Some(2.25),
+                steps: 5120,
+                elapsed: Duration::from_millis(81_500),
+            },
+        ] {
+            assert_eq!(progress.to_string().parse(), Ok(progress));
+        }
+
+        let parsed = "\nbest-score 3\nepisode 7\n".parse();
+        assert_eq!(
+            parsed,
+            Ok(Progress {
+                episode: 7,
+                best_score: Some(3.0),
+                ..Progress::default()
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_progress_is_rejected() {
+        for text in [
+            "episode",
+            "episode -1",
+            "best-score high",
+            "steps 1.5",
+            "elapsed -2",
+            "elapsed inf",
+            "epoch 3",
+        ] {
+            assert!(text.parse::<Progress>().is_err(), "accepted {text:?}");
+        }
+    }
+
+    #[test]
+    fn same_settings_are_accepted() {
+        let settings = settings(&Args::default(), &());
+        assert!(check_settings(&settings, &settings).is_ok());
+    }
+
+    #[test]
+    fn changed_settings_are_named() {
+        let saved = "agent ()\nwrap false\nactions absolute\n";
+
+        let err = check_settings(saved, "agent ()\nwrap true\nactions absolute\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(
+            err.to_string(),
+            "checkpoint was trained with 'wrap false', not 'wrap true'"
+        );
+
+        assert!(check_settings(saved, "agent ()\nwrap false\n").is_err());
+        assert!(check_settings(saved, &format!("{saved}reward-win 1\n")).is_err());
+    }
+
+    #[test]
+    fn levels_are_told_apart_by_layout() {
+        let mut args = Args {
+            level: Some(Level::builtin("box").unwrap()),
+            ..Args::default()
+        };
+        let saved = settings(&args, &());
+
+        let mut level = Level::builtin("cross").unwrap();
+        level.name = String::from("box");
+        args.level = Some(level);
+        assert!(check_settings(&saved, &settings(&args, &())).is_err());
+    }
+}
@@ -0,0 +1,470 @@
+use std::path::PathBuf;
+
+#[cfg(all(feature = "tui", not(feature = "rl")))]
+use snake::Speed;
+
+#[cfg(feature = "rl")]
+use snake::env::{ActionSpace, EpisodeLimits};
+#[cfg(all(feature = "tui", feature = "rl"))]
+use snake::render::RenderPolicy;
+#[cfg(feature = "rl")]
+use snake::{observation, reward::RewardConfig};
+
+#[cfg(feature = "rl")]
+use crate::backend::Backend;
+use snake::{level, Difficulty, Game, Level, Rules};
+
+const USAGE: &str = "\
+Usage: snake [OPTIONS]
+       snake play --model <FILE> [OPTIONS]
+
+Commands:
+  play               Watch a trained model play instead of training
+
+Options:
+  --width <WIDTH>    Board width [default: 17]
+  --height <HEIGHT>  Board height [default: 15]
+  --level <LEVEL>    Built-in level name or level file, sets the board size
+  --seed <SEED>      Seed of the first game [default: random]
+  --wrap             Wrap around the board edges instead of dying on them
+  --difficulty <DIFFICULTY>
+                     Starting speed: slow, normal, fast or insane [default: normal]
+  --progressive      Speed up as the score grows
+  --speed-increase <RATE>
+                     Ticks per second gained when speeding up [default: 1]
+  --speed-every <APPLES>
+                     Apples eaten between speed ups [default: 2]
+  --max-speed <RATE>
+                     Fastest ticks per second [default: 3 times the starting speed]
+  --record <DIR>     Save a replay of every finished game in DIR
+  --scores <FILE>    High score table [default: $SNAKE_HIGH_SCORES, or in the user data directory]
+  --replay <FILE>    Play back a saved replay
+  --model <FILE>     Model played by 'play', at the '--difficulty' speed
+  --backend <BACKEND>
+                     Training backend: wgpu or ndarray, if enabled [default: wgpu]
+  --actions <ACTIONS>
+                     Agent actions: absolute directions or relative turns [default: absolute]
+  --observation <ENCODER>
+                     What the agent sees: distances, danger, grid or window [default: distances]
+  --rewards <FILE>   Reward configuration file, overridden by the options below
+  --reward-step <REWARD>
+                     Reward on every step [default: -0.01]
+  --reward-apple <REWARD>
+                     Reward for eating an apple [default: 1]
+  --reward-death <REWARD>
+                     Reward for crashing [default: 0]
+  --reward-win <REWARD>
+                     Reward for filling the board [default: 0]
+  --reward-distance <REWARD>
+                     Reward per cell moved towards the apple [default: 0]
+  --reward-starvation <REWARD>
+                     Reward every '--starvation-steps' steps without eating [default: 0]
+  --starvation-steps <STEPS>
+                     Steps without eating before starving, never if 0 [default: 0]
+  --max-steps-without-eating <STEPS>
+                     Truncate episodes after this many steps without eating [default: none]
+  --max-steps <STEPS>
+                     Truncate episodes after this many steps [default: none]
+  --episodes <EPISODES>
+                     Episodes to train for [default: 256]
+  --checkpoints <DIR>
+                     Save the model and the best one by evaluation score in DIR
+  --checkpoint-every <EPISODES>
+                     Episodes between checkpoints [default: 16]
+  --eval-episodes <EPISODES>
+                     Greedy games played to score a checkpoint [default: 4]
+  --resume           Continue training from the latest checkpoint in '--checkpoints'
+  --metrics <FILE>   Write the metrics of every episode to FILE, as .csv or .jsonl
+  --render <POLICY>  Games drawn while training: never, steps:N, episodes:N or eval [default: steps:1]
+  -h, --help         Print help";
+
+#[derive(Debug)]
+pub struct Args {
+    pub width: usize,
+    pub height: usize,
+    pub level: Option<Level>,
+    pub seed: Option<u64>,
+    pub wrap: bool,
+    pub difficulty: Difficulty,
+    pub progressive: bool,
+    pub speed_increase: Option<f64>,
+    pub speed_every: Option<usize>,
+    pub max_speed: Option<f64>,
+    pub record: Option<PathBuf>,
+    pub scores: Option<PathBuf>,
+    #[cfg(feature = "tui")]
+    pub replay: Option<PathBuf>,
+    #[cfg(all(feature = "tui", feature = "rl"))]
+    pub model: Option<PathBuf>,
+    #[cfg(feature = "rl")]
+    pub backend: Backend,
+    #[cfg(feature = "rl")]
+    pub actions: ActionSpace,
+    #[cfg(feature = "rl")]
+    pub observation: String,
+    #[cfg(feature = "rl")]
+    pub rewards: RewardConfig,
+    #[cfg(feature = "rl")]
+    pub limits: EpisodeLimits,
+    #[cfg(feature = "rl")]
+    pub episodes: usize,
+    #[cfg(feature = "rl")]
+    pub checkpoints: Option<PathBuf>,
+    #[cfg(feature = "rl")]
+    pub checkpoint_every: usize,
+    #[cfg(feature = "rl")]
+    pub eval_episodes: usize,
+    #[cfg(feature = "rl")]
+    pub resume: bool,
+    #[cfg(feature = "rl")]
+    pub metrics: Option<PathBuf>,
+    #[cfg(all(feature = "tui", feature = "rl"))]
+    pub render: RenderPolicy,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            width: 17,
+            height: 15,
+            level: None,
+            seed: None,
+            wrap: false,
+            difficulty: Difficulty::default(),
+            progressive: false,
+            speed_increase: None,
+            speed_every: None,
+            max_speed: None,
+            record: None,
+            scores: None,
+            #[cfg(feature = "tui")]
+            replay: None,
+            #[cfg(all(feature = "tui", feature = "rl"))]
+            model: None,
+            #[cfg(feature = "rl")]
+            backend: Backend::default(),
+            #[cfg(feature = "rl")]
+            actions: ActionSpace::default(),
+            #[cfg(feature = "rl")]
+            observation: String::from("distances"),
+            #[cfg(feature = "rl")]
+            rewards: RewardConfig::default(),
+            #[cfg(feature = "rl")]
+            limits: EpisodeLimits::default(),
+            #[cfg(feature = "rl")]
+            episodes: 256,
+            #[cfg(feature = "rl")]
+            checkpoints: None,
+            #[cfg(feature = "rl")]
+            checkpoint_every: 16,
+            #[cfg(feature = "rl")]
+            eval_episodes: 4,
+            #[cfg(feature = "rl")]
+            resume: false,
+            #[cfg(feature = "rl")]
+            metrics: None,
+            #[cfg(all(feature = "tui", feature = "rl"))]
+            render: RenderPolicy::default(),
+        }
+    }
+}
+
+impl Args {
+    pub fn parse() -> Self {
+        match Self::try_parse(std::env::args().skip(1)) {
+            Ok(args) => args,
+            Err(err) => {
+                eprintln!("error: {err}\n\n{USAGE}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    fn try_parse(iter: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut args = Self::default();
+        let mut iter = iter.peekable();
+        #[cfg(all(feature = "tui", feature = "rl"))]
+        let play = iter.next_if(|arg| arg == "play").is_some();
+        #[cfg(feature = "rl")]
+        let mut reward_overrides = Vec::new();
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--width" => args.width = parse_value(&arg, iter.next())?,
+                "--height" => args.height = parse_value(&arg, iter.next())?,
+                "--level" => {
+                    let level = parse_level(&arg, iter.next())?;
+                    args.width = level.width;
+                    args.height = level.height;
+                    args.level = Some(level);
+                }
+                "--seed" => args.seed = Some(parse_value(&arg, iter.next())?),
+                "--wrap" => args.wrap = true,
+                "--difficulty" => args.difficulty = parse_value(&arg, iter.next())?,
+                "--progressive" => args.progressive = true,
+                "--speed-increase" => args.speed_increase = Some(parse_value(&arg, iter.next())?),
+                "--speed-every" => args.speed_every = Some(parse_value(&arg, iter.next())?),
+                "--max-speed" => args.max_speed = Some(parse_value(&arg, iter.next())?),
+                "--record" => args.record = Some(parse_value(&arg, iter.next())?),
+                "--scores" => args.scores = Some(parse_value(&arg, iter.next())?),
+                #[cfg(feature = "tui")]
+                "--replay" => args.replay = Some(parse_value(&arg, iter.next())?),
+                #[cfg(all(feature = "tui", feature = "rl"))]
+                "--model" => args.model = Some(parse_value(&arg, iter.next())?),
+                #[cfg(feature = "rl")]
+                "--backend" => args.backend = parse_value(&arg, iter.next())?,
+                #[cfg(feature = "rl")]
+                "--actions" => args.actions = parse_value(&arg, iter.next())?,
+                #[cfg(feature = "rl")]
+                "--observation" => {
+                    args.observation = parse_value(&arg, iter.next())?;
+                    if observation::by_name(&args.observation).is_none() {
+                        return Err(format!(
+                            "unknown observation '{}' (expected one of: {})",
+                            args.observation,
+                            observation::NAMES.join(", ")
+                        ));
+                    }
+                }
+                #[cfg(feature = "rl")]
+                "--rewards" => {
+                    let path: PathBuf = parse_value(&arg, iter.next())?;
+                    args.rewards = RewardConfig::read(&path)
+                        .map_err(|err| format!("invalid rewards '{}': {err}", path.display()))?;
+                }
+                #[cfg(feature = "rl")]
+                "--reward-step"
+                | "--reward-apple"
+                | "--reward-death"
+                | "--reward-win"
+                | "--reward-distance"
+                | "--reward-starvation"
+                | "--starvation-steps" => {
+                    let value: String = parse_value(&arg, iter.next())?;
+                    reward_overrides.push((arg, value));
+                }
+                #[cfg(feature = "rl")]
+                "--max-steps-without-eating" => {
+                    args.limits.max_steps_without_eating = Some(parse_value(&arg, iter.next())?)
+                }
+                #[cfg(feature = "rl")]
+                "--max-steps" => args.limits.max_steps = Some(parse_value(&arg, iter.next())?),
+                #[cfg(feature = "rl")]
+                "--episodes" => args.episodes = parse_value(&arg, iter.next())?,
+                #[cfg(feature = "rl")]
+                "--checkpoints" => args.checkpoints = Some(parse_value(&arg, iter.next())?),
+                #[cfg(feature = "rl")]
+                "--checkpoint-every" => args.checkpoint_every = parse_value(&arg, iter.next())?,
+                #[cfg(feature = "rl")]
+                "--eval-episodes" => args.eval_episodes = parse_value(&arg, iter.next())?,
+                #[cfg(feature = "rl")]
+                "--resume" => args.resume = true,
+                #[cfg(feature = "rl")]
+                "--metrics" => args.metrics = Some(parse_value(&arg, iter.next())?),
+                #[cfg(all(feature = "tui", feature = "rl"))]
+                "--render" => args.render = parse_value(&arg, iter.next())?,
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    std::process::exit(0);
+                }
+                _ => return Err(format!("unexpected argument '{arg}'")),
+            }
+        }
+
+        if args.width < Game::MIN_WIDTH || args.height < Game::MIN_HEIGHT {
+            return Err(format!(
+                "board must be at least {}x{}",
+                Game::MIN_WIDTH,
+                Game::MIN_HEIGHT
+            ));
+        }
+
+        // Options take precedence over the rewards file wherever they are
+        #[cfg(feature = "rl")]
+        for (arg, value) in reward_overrides {
+            let key = arg.trim_start_matches("--").trim_start_matches("reward-");
+            args.rewards
+                .set(key, &value)
+                .map_err(|_| format!("invalid value '{value}' for '{arg}'"))?;
+        }
+
+        #[cfg(feature = "rl")]
+        if args.checkpoint_every == 0 {
+            return Err(String::from("'--checkpoint-every' must be at least 1"));
+        }
+
+        #[cfg(feature = "rl")]
+        if args.eval_episodes == 0 {
+            return Err(String::from("'--eval-episodes' must be at least 1"));
+        }
+
+        #[cfg(all(feature = "tui", feature = "rl"))]
+        match (play, &args.model) {
+            (true, None) => return Err(String::from("'play' needs '--model'")),
+            (false, Some(_)) => return Err(String::from("'--model' is only used by 'play'")),
+            _ => (),
+        }
+
+        #[cfg(feature = "rl")]
+        if args.resume && args.checkpoints.is_none() {
+            return Err(String::from("'--resume' needs '--checkpoints'"));
+        }
+
+        #[cfg(feature = "rl")]
+        if let Some(path) = &args.metrics {
+            if !matches!(
+                path.extension().and_then(|extension| extension.to_str()),
+                Some("csv" | "jsonl")
+            ) {
+                return Err(String::from("'--metrics' must end in .csv or .jsonl"));
+            }
+        }
+
+        if args.speed_every == Some(0) {
+            return Err(String::from("'--speed-every' must be at least 1"));
+        }
+
+        if args
+            .speed_increase
+            .is_some_and(|increase| !(increase.is_finite() && increase >= 0.0))
+        {
+            return Err(String::from("'--speed-increase' must not be negative"));
+        }
+
+        if args
+            .max_speed
+            .is_some_and(|max| !(max.is_finite() && max > 0.0))
+        {
+            return Err(String::from("'--max-speed' must be positive"));
+        }
+
+        Ok(args)
+    }
+
+    /// Speed schedule, progressive if any of its settings were given.
+    #[cfg(all(feature = "tui", not(feature = "rl")))]
+    pub fn speed(&self) -> Speed {
+        let frame_rate = self.difficulty.frame_rate();
+
+        if !self.progressive
+            && self.speed_increase.is_none()
+            && self.speed_every.is_none()
+            && self.max_speed.is_none()
+        {
+            return Speed::constant(frame_rate);
+        }
+
+        let default = Speed::progressive(frame_rate);
+        Speed {
+            base: frame_rate,
+            increase: self.speed_increase.unwrap_or(default.increase),
+            every: self.speed_every.unwrap_or(default.every),
+            max: self.max_speed.unwrap_or(default.max),
+        }
+    }
+
+    pub fn game(&self) -> Game {
+        let level = match &self.level {
+            Some(level) => level.clone(),
+            None => Level::empty(self.width, self.height),
+        };
+        let mut game = Game::from_level(level, self.seed.unwrap_or_else(|| fastrand::u64(..)));
+
+        game.set_rules(Rules {
+            wrap_around: self.wrap,
+        });
+
+        if let Some(dir) = &self.record {
+            game.record_to(dir);
+        }
+
+        #[cfg(feature = "rl")]
+        game.set_rewards(self.rewards);
+        #[cfg(feature = "rl")]
+        game.set_limits(self.limits);
+        #[cfg(feature = "rl")]
+        game.set_observation(
+            observation::by_name(&self.observation).expect("observation checked when parsing"),
+        );
+
+        game
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(arg: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("a value is required for '{arg}'"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for '{arg}'"))
+}
+
+fn parse_level(arg: &str, value: Option<String>) -> Result<Level, String> {
+    let value = value.ok_or_else(|| format!("a value is required for '{arg}'"))?;
+
+    if let Some(level) = Level::builtin(&value) {
+        return Ok(level);
+    }
+
+    Level::read(&value).map_err(|err| {
+        let builtin: Vec<&str> = level::BUILTIN.iter().map(|(name, _)| *name).collect();
+        format!(
+            "invalid level '{value}': {err} (built-in levels: {})",
+            builtin.join(", ")
+        )
+    })
+}
+
+#[cfg(all(test, feature = "rl"))]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::try_parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn reward_options_override_the_file() {
+        let path = std::env::temp_dir().join(format!("snake-rewards-{}", std::process::id()));
+        fs::write(&path, "apple 3\ndeath -2\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        // Wherever the options are
+        for args in [
+            ["--reward-apple", "5", "--rewards", path],
+            ["--rewards", path, "--reward-apple", "5"],
+        ] {
+            let rewards = parse(&args).unwrap().rewards;
+            assert_eq!(rewards.apple, 5.0);
+            assert_eq!(rewards.death, -2.0);
+        }
+        fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn models_are_played_by_the_play_command() {
+        let args = parse(&["play", "--model", "best.mpk", "--wrap"]).unwrap();
+        assert_eq!(args.model, Some(PathBuf::from("best.mpk")));
+
+        for args in [
+            &["play"][..],
+            &["--model", "best.mpk"],
+            &["--wrap", "play", "--model", "best.mpk"],
+        ] {
+            assert!(parse(args).is_err(), "accepted {args:?}");
+        }
+    }
+
+    #[test]
+    fn invalid_rewards_are_rejected() {
+        for args in [
+            ["--reward-step", "NaN"],
+            ["--reward-win", "inf"],
+            ["--starvation-steps", "-1"],
+        ] {
+            assert!(parse(&args).is_err(), "accepted {args:?}");
+        }
+    }
+}
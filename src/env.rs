@@ -0,0 +1,399 @@
+use std::borrow::{Borrow, BorrowMut};
+use std::cell::RefCell;
+use std::fmt;
+use std::str::FromStr;
+
+use fastrand::Rng;
+use rl::burn::tensor::TensorData;
+use rl::env::{DiscreteActionSpace, Environment};
+
+use crate::game::Game;
+use crate::snake::Direction;
+
+/// Sizes of the observations and actions of an environment, to build models
+/// that fit it.
+pub trait Shapes: DiscreteActionSpace {
+    /// Number of values in an observation.
+    fn observation_size(&self) -> usize;
+
+    /// Number of actions to choose from, which
+    /// [`actions`](DiscreteActionSpace::actions) must list.
+    const ACTION_COUNT: usize;
+}
+
+/// Panics unless the observations and actions of `env` have the sizes it
+/// reports.
+///
+/// This resets `env`.
+pub fn check_shapes<E: Shapes>(env: &mut E) {
+    let observation: TensorData = env.reset().into();
+    assert_eq!(
+        observation.shape,
+        [env.observation_size()],
+        "observation shape does not match the observation size of the environment",
+    );
+
+    assert_eq!(
+        env.actions().len(),
+        E::ACTION_COUNT,
+        "number of actions does not match the action count of the environment",
+    );
+}
+
+/// Limits cutting episodes short, none by default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeLimits {
+    /// Steps the snake may take without eating.
+    pub max_steps_without_eating: Option<usize>,
+    /// Steps an episode may last.
+    pub max_steps: Option<usize>,
+}
+
+/// Why an episode ended.
+///
+/// A terminated episode has no future rewards, while a truncated one was
+/// only cut short: its last state is still worth bootstrapping from, so
+/// [`Environment::step`] keeps returning it even though the environment is
+/// no longer active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpisodeEnd {
+    /// The snake crashed or filled the board.
+    Terminated,
+    /// An [`EpisodeLimits`] was reached.
+    Truncated,
+}
+
+/// Actions an agent picks from to steer the snake.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionSpace {
+    /// Every [`Direction`], the reversal being ignored, as played by [`Game`].
+    #[default]
+    Absolute,
+    /// A [`Turn`] relative to the heading, as played by [`Relative`].
+    Relative,
+}
+
+impl ActionSpace {
+    pub const VARIANTS: [ActionSpace; 2] = [ActionSpace::Absolute, ActionSpace::Relative];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ActionSpace::Absolute => "absolute",
+            ActionSpace::Relative => "relative",
+        }
+    }
+}
+
+impl fmt::Display for ActionSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ActionSpace {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::VARIANTS
+            .into_iter()
+            .find(|space| space.name() == s)
+            .ok_or_else(|| format!("unknown action space '{s}'"))
+    }
+}
+
+/// A turn relative to the heading of the snake.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    Left = 0,
+    #[default]
+    Straight = 1,
+    Right = 2,
+}
+
+impl Turn {
+    pub const VARIANTS: [Turn; 3] = [Turn::Left, Turn::Straight, Turn::Right];
+
+    /// Direction the snake heads to after turning from `heading`.
+    pub fn apply(self, heading: Direction) -> Direction {
+        match self {
+            Turn::Left => heading.counter_clockwise(),
+            Turn::Straight => heading,
+            Turn::Right => heading.clockwise(),
+        }
+    }
+}
+
+impl From<Turn> for TensorData {
+    fn from(value: Turn) -> Self {
+        TensorData::new(vec![value as u8], vec![1])
+    }
+}
+
+impl From<isize> for Turn {
+    fn from(value: isize) -> Self {
+        match value {
+            0 => Turn::Left,
+            1 => Turn::Straight,
+            2 => Turn::Right,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// A [`Game`] steered with [`Turn`]s instead of directions, so that no
+/// action is wasted on reversing.
+#[derive(Debug)]
+pub struct Relative {
+    game: Game,
+    action_rng: RefCell<Rng>,
+}
+
+impl Relative {
+    pub fn new(game: Game) -> Self {
+        let action_rng = RefCell::new(Rng::with_seed(!game.seed()));
+        Self { game, action_rng }
+    }
+
+    pub fn into_inner(self) -> Game {
+        self.game
+    }
+}
+
+impl Borrow<Game> for Relative {
+    fn borrow(&self) -> &Game {
+        &self.game
+    }
+}
+
+impl BorrowMut<Game> for Relative {
+    fn borrow_mut(&mut self) -> &mut Game {
+        &mut self.game
+    }
+}
+
+impl DiscreteActionSpace for Relative {
+    fn actions(&self) -> Vec<Self::Action> {
+        Turn::VARIANTS.to_vec()
+    }
+}
+
+impl Shapes for Relative {
+    const ACTION_COUNT: usize = Turn::VARIANTS.len();
+
+    fn observation_size(&self) -> usize {
+        self.game.observation_size()
+    }
+}
+
+impl Environment for Relative {
+    type State = <Game as Environment>::State;
+    type Action = Turn;
+
+    fn is_active(&self) -> bool {
+        self.game.is_active()
+    }
+
+    fn reset(&mut self) -> Self::State {
+        let state = self.game.reset();
+        // Like the game, pick the same random actions again from a seed
+        *self.action_rng.get_mut() = Rng::with_seed(!self.game.seed());
+        state
+    }
+
+    fn random_action(&self) -> Self::Action {
+        self.game.count_random_action();
+        *self
+            .action_rng
+            .borrow_mut()
+            .choice(Turn::VARIANTS.iter())
+            .unwrap()
+    }
+
+    fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32) {
+        let direction = action.apply(self.game.snake().direction());
+        Environment::step(&mut self.game, direction)
+    }
+}
+
+/// Watches the games an agent plays, outside of the environment.
+pub trait Observer {
+    /// Called after every step of `game`.
+    fn step(&mut self, game: &Game);
+}
+
+/// An environment reporting every step to an [`Observer`].
+#[derive(Debug)]
+pub struct Observed<E, O> {
+    env: E,
+    observer: O,
+}
+
+impl<E, O> Observed<E, O> {
+    pub fn new(env: E, observer: O) -> Self {
+        Self { env, observer }
+    }
+
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    pub fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E, O> Borrow<Game> for Observed<E, O>
+where
+    E: Borrow<Game>,
+{
+    fn borrow(&self) -> &Game {
+        self.env.borrow()
+    }
+}
+
+impl<E, O> BorrowMut<Game> for Observed<E, O>
+where
+    E: BorrowMut<Game>,
+{
+    fn borrow_mut(&mut self) -> &mut Game {
+        self.env.borrow_mut()
+    }
+}
+
+impl<E: Shapes + BorrowMut<Game>, O: Observer> DiscreteActionSpace for Observed<E, O> {
+    fn actions(&self) -> Vec<Self::Action> {
+        self.env.actions()
+    }
+}
+
+impl<E: Shapes + BorrowMut<Game>, O: Observer> Shapes for Observed<E, O> {
+    const ACTION_COUNT: usize = E::ACTION_COUNT;
+
+    fn observation_size(&self) -> usize {
+        self.env.observation_size()
+    }
+}
+
+impl<E: Environment + BorrowMut<Game>, O: Observer> Environment for Observed<E, O> {
+    type State = E::State;
+    type Action = E::Action;
+
+    fn is_active(&self) -> bool {
+        self.env.is_active()
+    }
+
+    fn reset(&mut self) -> Self::State {
+        self.env.reset()
+    }
+
+    fn random_action(&self) -> Self::Action {
+        self.env.random_action()
+    }
+
+    fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32) {
+        let result = self.env.step(action);
+        let game: &Game = self.env.borrow();
+        self.observer.step(game);
+        result
+    }
+}
+
+/// A step of an episode: the state, the action taken in it, the reward and
+/// the next state, `None` if the episode terminated.
+pub type Transition<E> = (
+    <E as Environment>::State,
+    <E as Environment>::Action,
+    f32,
+    Option<<E as Environment>::State>,
+);
+
+/// An environment keeping the transitions of its current episode, to measure
+/// how well a model predicts them.
+pub struct Recorded<E: Environment> {
+    env: E,
+    state: Option<E::State>,
+    transitions: Vec<Transition<E>>,
+}
+
+impl<E: Environment> Recorded<E> {
+    pub fn new(env: E) -> Self {
+        Self {
+            env,
+            state: None,
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Transitions since the last reset, in order.
+    pub fn transitions(&self) -> &[Transition<E>] {
+        &self.transitions
+    }
+
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.env
+    }
+
+    pub fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E: Environment + Borrow<Game>> Borrow<Game> for Recorded<E> {
+    fn borrow(&self) -> &Game {
+        self.env.borrow()
+    }
+}
+
+impl<E: Environment + BorrowMut<Game>> BorrowMut<Game> for Recorded<E> {
+    fn borrow_mut(&mut self) -> &mut Game {
+        self.env.borrow_mut()
+    }
+}
+
+impl<E: Shapes> DiscreteActionSpace for Recorded<E> {
+    fn actions(&self) -> Vec<Self::Action> {
+        self.env.actions()
+    }
+}
+
+impl<E: Shapes> Shapes for Recorded<E> {
+    const ACTION_COUNT: usize = E::ACTION_COUNT;
+
+    fn observation_size(&self) -> usize {
+        self.env.observation_size()
+    }
+}
+
+impl<E: Environment> Environment for Recorded<E> {
+    type State = E::State;
+    type Action = E::Action;
+
+    fn is_active(&self) -> bool {
+        self.env.is_active()
+    }
+
+    fn reset(&mut self) -> Self::State {
+        let state = self.env.reset();
+        self.transitions.clear();
+        self.state = Some(state.clone());
+        state
+    }
+
+    fn random_action(&self) -> Self::Action {
+        self.env.random_action()
+    }
+
+    fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32) {
+        let (next_state, reward) = self.env.step(action.clone());
+        if let Some(state) = self.state.take() {
+            self.transitions
+                .push((state, action, reward, next_state.clone()));
+        }
+        self.state = next_state.clone();
+        (next_state, reward)
+    }
+}
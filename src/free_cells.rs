@@ -0,0 +1,95 @@
+use fastrand::Rng;
+
+use crate::point::Point;
+
+/// Cells of a board nothing stands on, to pick one at random in constant
+/// time.
+///
+/// Cells are kept in a list, with the position of every free cell in it
+/// indexed by cell so that adding and removing one is constant time too.
+#[derive(Debug, Clone)]
+pub struct FreeCells {
+    width: usize,
+    height: usize,
+    cells: Vec<Point>,
+    positions: Vec<Option<usize>>,
+}
+
+impl FreeCells {
+    /// Every cell of a `width` by `height` board.
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut free_cells = Self {
+            width,
+            height,
+            cells: Vec::with_capacity(width * height),
+            positions: vec![None; width * height],
+        };
+
+        for x in 0..width as isize {
+            for y in 0..height as isize {
+                free_cells.insert(Point::new(x, y));
+            }
+        }
+
+        free_cells
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.index(point)
+            .is_some_and(|index| self.positions[index].is_some())
+    }
+
+    /// Frees `point`, if on the board.
+    pub fn insert(&mut self, point: Point) {
+        let Some(index) = self.index(&point) else {
+            return;
+        };
+
+        if self.positions[index].is_none() {
+            self.positions[index] = Some(self.cells.len());
+            self.cells.push(point);
+        }
+    }
+
+    /// Takes `point`, if on the board.
+    pub fn remove(&mut self, point: &Point) {
+        let Some(position) = self
+            .index(point)
+            .and_then(|index| self.positions[index].take())
+        else {
+            return;
+        };
+
+        // Fill the gap with the last cell
+        self.cells.swap_remove(position);
+        if let Some(moved) = self.cells.get(position) {
+            let index = self.index(moved).unwrap();
+            self.positions[index] = Some(position);
+        }
+    }
+
+    /// A free cell drawn uniformly with `rng`, `None` if the board is full.
+    pub fn choose(&self, rng: &mut Rng) -> Option<Point> {
+        if self.cells.is_empty() {
+            return None;
+        }
+
+        Some(self.cells[rng.usize(..self.cells.len())])
+    }
+
+    fn index(&self, point: &Point) -> Option<usize> {
+        let (x, y) = (
+            usize::try_from(point.x).ok()?,
+            usize::try_from(point.y).ok()?,
+        );
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+}
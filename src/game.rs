@@ -1,11 +1,19 @@
 #[cfg(all(feature = "tui", not(feature = "rl")))]
 use std::time::{Duration, Instant};
 
-#[cfg(all(feature = "tui", feature = "rl"))]
-use ratatui::widgets::WidgetRef;
+#[cfg(feature = "rl")]
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+
+use fastrand::Rng;
 
 #[cfg(all(feature = "tui", not(feature = "rl")))]
-use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    widgets::Paragraph,
+};
 
 #[cfg(feature = "tui")]
 use ratatui::{
@@ -15,184 +23,492 @@ use ratatui::{
 #[cfg(feature = "rl")]
 use rl::env::{DiscreteActionSpace, Environment, Report};
 
+#[cfg(feature = "rl")]
+use crate::env::{EpisodeEnd, EpisodeLimits, Shapes};
+#[cfg(feature = "rl")]
+use crate::observation::{self, Distances, Observation};
+#[cfg(feature = "rl")]
+use crate::reward::RewardConfig;
+
 use crate::apple::Apple;
+use crate::free_cells::FreeCells;
+#[cfg(all(feature = "tui", not(feature = "rl")))]
+use crate::high_score::HighScores;
+use crate::level::Level;
 use crate::point::Point;
+use crate::replay::Replay;
+use crate::rules::Rules;
 use crate::snake::{Direction, Snake};
+#[cfg(all(feature = "tui", not(feature = "rl")))]
+use crate::speed::Speed;
 
-#[cfg(all(feature = "tui", feature = "rl"))]
-use crate::TERMINAL;
+#[cfg(all(feature = "tui", not(feature = "rl")))]
+const MAX_NAME_LENGTH: usize = 12;
 
+/// A game of snake on a [`Level`].
 #[derive(Debug)]
-pub struct Game<const WIDTH: usize, const HEIGHT: usize> {
+pub struct Game {
+    level: Level,
+    rules: Rules,
+    #[cfg(all(feature = "tui", not(feature = "rl")))]
+    speed: Speed,
+    #[cfg(all(feature = "tui", not(feature = "rl")))]
+    high_scores: Option<HighScores>,
     #[cfg(all(feature = "tui", not(feature = "rl")))]
-    frame_rate: f64,
+    screen: Screen,
     apple: Apple,
+    pending_apples: VecDeque<Point>,
+    free_cells: FreeCells,
     snake: Snake,
     direction: Direction,
+    queued_directions: VecDeque<Direction>,
     state: GameState,
+    seed: u64,
+    rng: Rng,
+    inputs: Vec<Direction>,
+    record_dir: Option<PathBuf>,
+    #[cfg(feature = "rl")]
+    action_rng: RefCell<Rng>,
+    /// Actions picked at random by the agent since the last step.
+    #[cfg(feature = "rl")]
+    random_actions: Cell<usize>,
+    #[cfg(feature = "rl")]
+    observation: Box<dyn Observation>,
+    #[cfg(feature = "rl")]
+    rewards: RewardConfig,
+    #[cfg(feature = "rl")]
+    steps_since_apple: usize,
+    #[cfg(feature = "rl")]
+    limits: EpisodeLimits,
+    #[cfg(feature = "rl")]
+    is_truncated: bool,
     #[cfg(feature = "rl")]
     pub report: Report,
 }
 
+/// What the side panel of the interactive game shows.
+#[cfg(all(feature = "tui", not(feature = "rl")))]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+enum Screen {
+    #[default]
+    Board,
+    NameEntry(String),
+    HighScores,
+}
+
+/// Whether a [`Game`] is still being played.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-enum GameState {
+pub enum GameState {
     #[default]
     Running,
+    Paused,
+    /// The snake crashed.
+    Over,
+    /// The snake filled the board.
+    Won,
+    /// The player left the game.
     Quit,
 }
 
-impl<const WIDTH: usize, const HEIGHT: usize> Default for Game<WIDTH, HEIGHT> {
-    fn default() -> Self {
-        let initial_direction = Direction::default();
-
-        let apple = Point::new(
-            (WIDTH as f64 * 3.0 / 4.0) as isize,
-            (HEIGHT as f64 / 2.0) as isize,
-        )
-        .into();
+impl Game {
+    /// Smallest board width fitting the initial snake and apple.
+    pub const MIN_WIDTH: usize = Level::MIN_WIDTH;
+    /// Smallest board height fitting the initial snake and apple.
+    pub const MIN_HEIGHT: usize = Level::MIN_HEIGHT;
+    /// Number of turns [`queue_direction`](Self::queue_direction) keeps.
+    pub const INPUT_BUFFER_SIZE: usize = 3;
+
+    /// Creates a game with a random seed.
+    ///
+    /// See [`with_seed`](Self::with_seed).
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_seed(width, height, fastrand::u64(..))
+    }
 
-        let snake = Snake::new(
-            Point::new(3, (HEIGHT as f64 / 2.0) as isize),
-            2,
-            initial_direction,
-        );
+    /// Creates a game on an [`empty`](Level::empty) board.
+    ///
+    /// Games created with the same seed and fed the same directions play out
+    /// identically.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the board is smaller than [`MIN_WIDTH`](Self::MIN_WIDTH) by
+    /// [`MIN_HEIGHT`](Self::MIN_HEIGHT).
+    pub fn with_seed(width: usize, height: usize, seed: u64) -> Self {
+        Self::from_level(Level::empty(width, height), seed)
+    }
 
-        Self {
+    /// Creates a game starting from `level`.
+    pub fn from_level(level: Level, seed: u64) -> Self {
+        let initial_direction = level.direction;
+
+        let mut pending_apples: VecDeque<Point> = level.apples.iter().copied().collect();
+        let apple = pending_apples.pop_front();
+
+        let snake = level.snake();
+
+        // Walls are taken in board order, as iterating their set changes
+        // order from run to run and would move the apples of a seed
+        let mut free_cells = FreeCells::new(level.width, level.height);
+        let walls = (0..level.height as isize)
+            .flat_map(|y| (0..level.width as isize).map(move |x| Point::new(x, y)))
+            .filter(|point| level.walls.contains(point));
+        snake
+            .body()
+            .into_iter()
+            .copied()
+            .chain(walls)
+            .for_each(|point| free_cells.remove(&point));
+
+        let mut game = Self {
+            #[cfg(all(feature = "tui", not(feature = "rl")))]
+            speed: Speed::default(),
+            #[cfg(all(feature = "tui", not(feature = "rl")))]
+            high_scores: None,
             #[cfg(all(feature = "tui", not(feature = "rl")))]
-            frame_rate: 10.0,
-            apple,
+            screen: Screen::default(),
+            apple: apple.unwrap_or(level.head).into(),
+            pending_apples,
+            free_cells,
             snake,
             direction: initial_direction,
+            queued_directions: VecDeque::with_capacity(Self::INPUT_BUFFER_SIZE),
             state: GameState::default(),
+            level,
+            rules: Rules::default(),
+            seed,
+            rng: Rng::with_seed(seed),
+            inputs: Vec::new(),
+            record_dir: None,
+            #[cfg(feature = "rl")]
+            action_rng: RefCell::new(Rng::with_seed(!seed)),
+            #[cfg(feature = "rl")]
+            random_actions: Cell::new(0),
+            #[cfg(feature = "rl")]
+            observation: Box::new(Distances),
             #[cfg(feature = "rl")]
-            report: Report::new(vec!["score", "reward", "steps"]),
+            rewards: RewardConfig::default(),
+            #[cfg(feature = "rl")]
+            steps_since_apple: 0,
+            #[cfg(feature = "rl")]
+            limits: EpisodeLimits::default(),
+            #[cfg(feature = "rl")]
+            is_truncated: false,
+            #[cfg(feature = "rl")]
+            report: Report::new(
+                ["score", "reward", "steps", "random_actions", "truncated"]
+                    .into_iter()
+                    .chain(RewardConfig::REPORT_KEYS)
+                    .collect(),
+            ),
+        };
+
+        if apple.is_none() {
+            game.place_apple();
         }
+
+        game
+    }
+
+    pub fn level(&self) -> &Level {
+        &self.level
+    }
+
+    pub fn width(&self) -> usize {
+        self.level.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.level.height
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+        self.snake.set_wrap(
+            rules
+                .wrap_around
+                .then_some((self.level.width, self.level.height)),
+        );
     }
-}
 
-impl<const WIDTH: usize, const HEIGHT: usize> Game<WIDTH, HEIGHT> {
-    fn step(&mut self) {
+    /// Sets how the game is encoded for agents, [`Distances`] by default.
+    #[cfg(feature = "rl")]
+    pub fn set_observation(&mut self, observation: Box<dyn Observation>) {
+        self.observation = observation;
+    }
+
+    /// Sets the rewards given to agents.
+    #[cfg(feature = "rl")]
+    pub fn set_rewards(&mut self, rewards: RewardConfig) {
+        self.rewards = rewards;
+    }
+
+    #[cfg(feature = "rl")]
+    pub fn limits(&self) -> EpisodeLimits {
+        self.limits
+    }
+
+    /// Sets the limits after which agents' episodes are truncated.
+    #[cfg(feature = "rl")]
+    pub fn set_limits(&mut self, limits: EpisodeLimits) {
+        self.limits = limits;
+    }
+
+    /// Why the current episode ended, if it did.
+    #[cfg(feature = "rl")]
+    pub fn episode_end(&self) -> Option<EpisodeEnd> {
+        if self.is_truncated {
+            Some(EpisodeEnd::Truncated)
+        } else if self.is_running() {
+            None
+        } else {
+            Some(EpisodeEnd::Terminated)
+        }
+    }
+
+    /// Seed the current game was started with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Starts a new game on the same board, keeping the rest of the
+    /// configuration.
+    pub fn reset_with_seed(&mut self, seed: u64) {
+        let mut game = Self::from_level(self.level.clone(), seed);
+        game.set_rules(self.rules);
+        self.apple = game.apple;
+        self.pending_apples = game.pending_apples;
+        self.free_cells = game.free_cells;
+        self.snake = game.snake;
+        self.direction = game.direction;
+        self.queued_directions = game.queued_directions;
+        self.state = game.state;
+        self.seed = game.seed;
+        self.rng = game.rng;
+        self.inputs = game.inputs;
+        #[cfg(feature = "rl")]
+        {
+            self.action_rng = game.action_rng;
+            self.random_actions = game.random_actions;
+            self.steps_since_apple = game.steps_since_apple;
+            self.is_truncated = game.is_truncated;
+        }
+    }
+
+    /// Sets the direction the snake will turn to on the next [`step`](Self::step).
+    ///
+    /// Reversing onto itself is ignored by the snake.
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+    }
+
+    /// Queues a turn to be taken on a later [`step`](Self::step), one per
+    /// step.
+    ///
+    /// Turns that would not change the heading of the snake by then, or
+    /// reverse it, are dropped, as are turns beyond
+    /// [`INPUT_BUFFER_SIZE`](Self::INPUT_BUFFER_SIZE).
+    pub fn queue_direction(&mut self, direction: Direction) {
+        let heading = match self.queued_directions.back() {
+            Some(&queued) => queued,
+            None => self.snake.direction(),
+        };
+
+        if direction == heading
+            || direction == heading.opposite()
+            || self.queued_directions.len() == Self::INPUT_BUFFER_SIZE
+        {
+            return;
+        }
+
+        self.queued_directions.push_back(direction);
+    }
+
+    /// Starts a new game on the same board, seeded from the current one.
+    pub fn restart(&mut self) {
+        let seed = self.rng.u64(..);
+        self.reset_with_seed(seed);
+    }
+
+    /// Advances the game by one tick, unless it is paused or over.
+    pub fn step(&mut self) {
+        if !self.is_running() {
+            return;
+        }
+
+        if let Some(direction) = self.queued_directions.pop_front() {
+            self.direction = direction;
+        }
+
+        self.inputs.push(self.direction);
         self.snake.turn(self.direction);
 
-        if self.is_facing_bound(self.snake.head(), self.snake.direction()) {
-            self.quit();
+        if self.is_blocked(&self.snake.next_head()) {
+            self.state = GameState::Over;
             return;
         }
 
+        let tail = *self.snake.tail();
+        let was_growing = self.snake.is_growing();
+
         self.snake.step();
 
         if self.snake.is_dead() {
-            self.quit();
+            self.state = GameState::Over;
             return;
         }
 
+        // Free the tail first, the head may have moved onto it, and only if
+        // no other part of the snake still covers it
+        if !was_growing && !self.snake.contains(&tail) {
+            self.free_cells.insert(tail);
+        }
+        self.free_cells.remove(self.snake.head());
+
         if self.apple.position() == self.snake.head() {
             self.snake.grow();
 
-            self.spawn_apple();
+            self.place_apple();
         }
     }
 
-    #[cfg(feature = "rl")]
-    fn state(&mut self) -> [f32; 5] {
-        let apple = self.apple.position();
-        let snake_head = self.snake.head();
-
-        let apple_distance = (apple.x - snake_head.x).abs() + (apple.y - snake_head.y).abs();
+    pub fn snake(&self) -> &Snake {
+        &self.snake
+    }
 
-        let top_wall_distance = HEIGHT - snake_head.y as usize;
-        let right_wall_distance = WIDTH - snake_head.x as usize;
-        let bottom_wall_distance = snake_head.y as usize;
-        let left_wall_distance = snake_head.x;
+    pub fn apple(&self) -> &Apple {
+        &self.apple
+    }
 
-        [
-            apple_distance as f32,
-            right_wall_distance as f32,
-            left_wall_distance as f32,
-            top_wall_distance as f32,
-            bottom_wall_distance as f32,
-        ]
+    pub fn state(&self) -> GameState {
+        self.state
     }
 
-    #[cfg(feature = "tui")]
-    fn score(&self) -> usize {
-        self.snake.len() - 3
+    /// Sets the directory [`save_replay`](Self::save_replay) writes to.
+    ///
+    /// The TUI and the RL environment save every finished game there.
+    pub fn record_to(&mut self, dir: impl Into<PathBuf>) {
+        self.record_dir = Some(dir.into());
     }
 
-    #[rustfmt::skip]
-    fn is_facing_bound(&self, point: &Point, direction: Direction) -> bool {
-        match direction {
-            Direction::Up    => point.y == HEIGHT as isize - 1,
-            Direction::Right => point.x == WIDTH as isize - 1,
-            Direction::Down  => point.y == 0,
-            Direction::Left  => point.x == 0,
+    /// Replay of the current game so far.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            level: self.level.clone(),
+            rules: self.rules,
+            seed: self.seed,
+            score: self.score(),
+            inputs: self.inputs.clone(),
         }
     }
 
-    fn spawn_apple(&mut self) {
-        let mut obstructions: Vec<&Point> = self.snake.body().into_iter().collect();
-
-        // Get a random position index minus obstructions count
-        let possible_positions = WIDTH * HEIGHT - obstructions.len();
-        let mut i = fastrand::usize(1..possible_positions);
-
-        // Find the random point
-        let mut new_point = Point::new(0, 0);
-        'outer: for x in 0..WIDTH as isize {
-            new_point.x = x;
-            for y in 0..HEIGHT as isize {
-                new_point.y = y;
+    /// Saves a [`Replay`] of the game so far in the directory given to
+    /// [`record_to`](Self::record_to), named after the game seed. Does
+    /// nothing if there is none.
+    pub fn save_replay(&self) -> std::io::Result<()> {
+        let Some(dir) = &self.record_dir else {
+            return Ok(());
+        };
 
-                // If the point is on the snake, skip it and remove the point from the snake
-                if let Some(index) = obstructions.iter().position(|x| *x == &new_point) {
-                    obstructions.remove(index);
-                } else {
-                    i -= 1;
-                }
+        fs::create_dir_all(dir)?;
+        self.replay()
+            .write(dir.join(format!("{}.replay", self.seed)))
+    }
 
-                if i == 0 {
-                    break 'outer;
-                }
+    /// Offset from the snake head to the apple, the short way round when
+    /// wrapping around.
+    pub fn apple_offset(&self) -> (isize, isize) {
+        let head = self.snake.head();
+        let apple = self.apple.position();
+        let mut dx = apple.x - head.x;
+        let mut dy = apple.y - head.y;
+
+        // The apple may be closer through an edge
+        if self.rules.wrap_around {
+            let (width, height) = (self.level.width as isize, self.level.height as isize);
+            if dx.abs() > width / 2 {
+                dx -= width * dx.signum();
+            }
+            if dy.abs() > height / 2 {
+                dy -= height * dy.signum();
             }
         }
 
-        self.apple = new_point.into();
+        (dx, dy)
     }
 
-    #[cfg(all(feature = "tui", feature = "rl"))]
-    pub fn run(&mut self) -> std::io::Result<()> {
-        let mut terminal = TERMINAL.lock().unwrap();
+    /// Number of moves between the snake head and the apple, ignoring
+    /// obstacles.
+    pub fn apple_distance(&self) -> usize {
+        let (dx, dy) = self.apple_offset();
+        dx.unsigned_abs() + dy.unsigned_abs()
+    }
 
-        terminal.draw(|frame| frame.render_widget(&self, frame.area()))?;
+    /// Number of apples eaten so far.
+    pub fn score(&self) -> usize {
+        // The snake only grows on the step after eating
+        self.snake.len() + usize::from(self.snake.is_growing()) - self.level.length
+    }
 
-        // Reset terminal cursor at the end of viewport
-        let area = terminal.get_frame().area();
-        terminal.set_cursor_position((0, area.height + area.y + 1))?;
+    /// Whether moving onto `point` ends the game, not counting the snake.
+    fn is_blocked(&self, point: &Point) -> bool {
+        !self.level.contains(point) || self.level.walls.contains(point)
+    }
 
-        Ok(())
+    /// Moves the apple to a new cell, or wins the game if none is left.
+    fn place_apple(&mut self) {
+        match self.spawn_apple() {
+            Some(apple) => self.apple = apple,
+            None => self.state = GameState::Won,
+        }
+    }
+
+    /// Next apple, `None` if the snake and the walls fill the board.
+    ///
+    /// Apples of the level come first, then every free cell is as likely.
+    fn spawn_apple(&mut self) -> Option<Apple> {
+        while let Some(point) = self.pending_apples.pop_front() {
+            if self.free_cells.contains(&point) {
+                return Some(point.into());
+            }
+        }
+
+        self.free_cells.choose(&mut self.rng).map(Apple::from)
     }
 
     #[cfg(all(feature = "tui", not(feature = "rl")))]
     pub fn run<B: Backend>(mut self, mut terminal: Terminal<B>) -> std::io::Result<()> {
-        terminal.draw(|frame| frame.render_widget(&self, frame.area()))?;
+        terminal.draw(|frame| self.draw(frame))?;
 
-        while self.is_running() {
+        while self.state != GameState::Quit {
             let now = Instant::now();
-            let timeout = Duration::from_secs_f64(1.0 / self.frame_rate);
+            let timeout = Duration::from_secs_f64(1.0 / self.speed.frame_rate(self.score()));
             let mut elapsed = now.elapsed();
 
             while elapsed < timeout {
                 if event::poll(timeout - elapsed)? {
                     self.handle_events()?;
+                    terminal.draw(|frame| self.draw(frame))?;
                 }
                 elapsed = now.elapsed();
             }
 
+            let was_running = self.is_running();
             self.step();
 
-            terminal.draw(|frame| frame.render_widget(&self, frame.area()))?;
+            if was_running && matches!(self.state, GameState::Over | GameState::Won) {
+                self.offer_high_score();
+            }
+
+            terminal.draw(|frame| self.draw(frame))?;
         }
 
+        self.save_replay()?;
+
         // Reset terminal cursor at the end of viewport
         let area = terminal.get_frame().area();
         terminal.set_cursor_position((0, area.height + area.y + 1))?;
@@ -200,95 +516,247 @@ impl<const WIDTH: usize, const HEIGHT: usize> Game<WIDTH, HEIGHT> {
         Ok(())
     }
 
-    fn is_running(&self) -> bool {
+    pub fn is_running(&self) -> bool {
         self.state == GameState::Running
     }
 
     #[cfg(all(feature = "tui", not(feature = "rl")))]
-    #[rustfmt::skip]
+    pub fn set_speed(&mut self, speed: Speed) {
+        self.speed = speed;
+    }
+
+    /// Offers to enter new records in `high_scores` when a game is over.
+    #[cfg(all(feature = "tui", not(feature = "rl")))]
+    pub fn set_high_scores(&mut self, high_scores: HighScores) {
+        self.high_scores = Some(high_scores);
+    }
+
+    /// Pauses a running game, or resumes a paused one.
+    pub fn toggle_pause(&mut self) {
+        self.state = match self.state {
+            GameState::Running => GameState::Paused,
+            GameState::Paused => GameState::Running,
+            state => state,
+        };
+    }
+
+    #[cfg(all(feature = "tui", not(feature = "rl")))]
     fn handle_events(&mut self) -> std::io::Result<()> {
         if let Event::Key(key) = event::read()? {
             if key.kind != KeyEventKind::Press {
                 return Ok(());
             }
-            match key.code {
-                KeyCode::Char('q') | KeyCode::Esc => self.quit(),
-                KeyCode::Up    => self.direction = Direction::Up,
-                KeyCode::Right => self.direction = Direction::Right,
-                KeyCode::Down  => self.direction = Direction::Down,
-                KeyCode::Left  => self.direction = Direction::Left,
-                _ => (),
+            match &mut self.screen {
+                Screen::Board => self.handle_board_key(key.code)?,
+                Screen::NameEntry(name) => match key.code {
+                    KeyCode::Enter => self.save_high_score()?,
+                    KeyCode::Esc => self.screen = Screen::Board,
+                    KeyCode::Backspace => {
+                        name.pop();
+                    }
+                    KeyCode::Char(c)
+                        if (c.is_alphanumeric() || " -_.".contains(c))
+                            && name.chars().count() < MAX_NAME_LENGTH =>
+                    {
+                        name.push(c)
+                    }
+                    _ => (),
+                },
+                Screen::HighScores => {
+                    self.screen = Screen::Board;
+                    if matches!(key.code, KeyCode::Char('q' | 'r') | KeyCode::Esc) {
+                        self.handle_board_key(key.code)?;
+                    }
+                }
             }
         }
         Ok(())
     }
 
-    fn quit(&mut self) {
-        self.state = GameState::Quit;
+    #[cfg(all(feature = "tui", not(feature = "rl")))]
+    #[rustfmt::skip]
+    fn handle_board_key(&mut self, code: KeyCode) -> std::io::Result<()> {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit(),
+            KeyCode::Char('p') | KeyCode::Char(' ') => self.toggle_pause(),
+            KeyCode::Char('r') => {
+                self.save_replay()?;
+                self.restart();
+            }
+            KeyCode::Char('h') if self.high_scores.is_some() => {
+                if self.is_running() {
+                    self.toggle_pause();
+                }
+                self.screen = Screen::HighScores;
+            }
+            KeyCode::Up    => self.queue_direction(Direction::Up),
+            KeyCode::Right => self.queue_direction(Direction::Right),
+            KeyCode::Down  => self.queue_direction(Direction::Down),
+            KeyCode::Left  => self.queue_direction(Direction::Left),
+            _ => (),
+        }
+        Ok(())
     }
-}
 
-#[cfg(all(feature = "tui", feature = "rl"))]
-impl<const WIDTH: usize, const HEIGHT: usize> WidgetRef for &mut Game<WIDTH, HEIGHT> {
-    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
-        let [area, _] = Layout::horizontal([
-            Constraint::Length((WIDTH * 2 + 2) as u16),
-            Constraint::Min(0),
-        ])
-        .areas(area);
+    #[cfg(all(feature = "tui", not(feature = "rl")))]
+    fn offer_high_score(&mut self) {
+        let Some(high_scores) = &self.high_scores else {
+            return;
+        };
 
-        Block::bordered()
-            .border_type(BorderType::Thick)
-            .render(area, buf);
+        if high_scores.is_record(&HighScores::board(self), self.score()) {
+            self.screen = Screen::NameEntry(String::new());
+        }
+    }
 
-        self.render_game(area.inner(Margin::new(1, 1)), buf);
+    #[cfg(all(feature = "tui", not(feature = "rl")))]
+    fn save_high_score(&mut self) -> std::io::Result<()> {
+        let Screen::NameEntry(name) = std::mem::take(&mut self.screen) else {
+            return Ok(());
+        };
+
+        let board = HighScores::board(self);
+        let score = self.score();
+
+        if let Some(high_scores) = &mut self.high_scores {
+            let name = match name.trim() {
+                "" => "anonymous",
+                name => name,
+            };
+            high_scores.insert(&board, score, name);
+            high_scores.save()?;
+        }
+
+        self.screen = Screen::HighScores;
+        Ok(())
+    }
+
+    pub fn quit(&mut self) {
+        self.state = GameState::Quit;
     }
 }
 
 #[cfg(feature = "tui")]
-impl<const WIDTH: usize, const HEIGHT: usize> Widget for &Game<WIDTH, HEIGHT> {
+impl Widget for &Game {
     fn render(self, area: Rect, buf: &mut Buffer)
     where
         Self: Sized,
     {
         let [area, _] = Layout::horizontal([
-            Constraint::Length((WIDTH * 2 + 2) as u16),
+            Constraint::Length((self.level.width * 2 + 2) as u16),
             Constraint::Min(0),
         ])
         .areas(area);
 
         Block::bordered()
-            .border_type(BorderType::Thick)
+            .border_type(self.border_type())
             .render(area, buf);
 
         self.render_game(area.inner(Margin::new(1, 1)), buf);
     }
 }
 
+#[cfg(all(feature = "tui", not(feature = "rl")))]
+impl Game {
+    fn draw(&self, frame: &mut Frame) {
+        frame.render_widget(self, frame.area());
+
+        let [_, side] = Layout::horizontal([
+            Constraint::Length((self.level.width * 2 + 2) as u16),
+            Constraint::Min(0),
+        ])
+        .areas(frame.area());
+
+        let lines = match &self.screen {
+            Screen::Board => {
+                #[rustfmt::skip]
+                let hints: &[&str] = match self.state {
+                    GameState::Running => &[" p  pause", " r  restart", " q  quit"],
+                    GameState::Paused  => &[" p  resume", " r  restart", " h  high scores", " q  quit"],
+                    _                  => &[" r  restart", " h  high scores", " q  quit"],
+                };
+
+                let mut lines = vec![
+                    Line::from(format!(" Score {}", self.score())),
+                    Line::from(format!(
+                        " Speed {:.1}/s",
+                        self.speed.frame_rate(self.score())
+                    )),
+                    Line::from(""),
+                ];
+                lines.extend(
+                    hints
+                        .iter()
+                        .filter(|hint| self.high_scores.is_some() || !hint.contains("high"))
+                        .map(|hint| Line::from(*hint).dark_gray()),
+                );
+                lines
+            }
+            Screen::NameEntry(name) => vec![
+                Line::from(" New high score!").bold(),
+                Line::from(format!(" Name: {name}_")),
+                Line::from(""),
+                Line::from(" enter  save").dark_gray(),
+                Line::from(" esc    skip").dark_gray(),
+            ],
+            Screen::HighScores => {
+                let mut lines = vec![Line::from(" High scores").bold()];
+                if let Some(high_scores) = &self.high_scores {
+                    let board = HighScores::board(self);
+                    lines.extend(high_scores.top(&board).enumerate().map(|(i, entry)| {
+                        Line::from(format!(" {:>2}. {:<12} {}", i + 1, entry.name, entry.score))
+                    }));
+                }
+                lines.push(Line::from(""));
+                lines.push(Line::from(" any key  back").dark_gray());
+                lines
+            }
+        };
+
+        Paragraph::new(lines).render(side, frame.buffer_mut());
+    }
+}
+
 #[cfg(feature = "tui")]
-impl<const WIDTH: usize, const HEIGHT: usize> Game<WIDTH, HEIGHT> {
+impl Game {
+    /// Open edges are drawn thinner than walls.
+    fn border_type(&self) -> BorderType {
+        if self.rules.wrap_around {
+            BorderType::Plain
+        } else {
+            BorderType::Thick
+        }
+    }
+
     fn render_game(&self, area: Rect, buf: &mut Buffer) {
         Canvas::default()
-            .x_bounds([0.0, (WIDTH * 2 - 1) as f64])
-            .y_bounds([0.0, (HEIGHT - 1) as f64])
+            .x_bounds([0.0, (self.level.width * 2 - 1) as f64])
+            .y_bounds([0.0, (self.level.height - 1) as f64])
             .marker(symbols::Marker::Block)
             .paint(|ctx| {
+                ctx.draw(&self.level);
                 ctx.draw(&self.apple);
                 ctx.draw(&self.snake);
 
-                if !self.is_running() {
-                    let game_over_text = "Game Over";
+                let lines = match self.state {
+                    GameState::Paused => vec![String::from("Paused")],
+                    GameState::Over => {
+                        vec![
+                            String::from("Game Over"),
+                            format!("Score: {}", self.score()),
+                        ]
+                    }
+                    GameState::Won => {
+                        vec![String::from("You Win!"), format!("Score: {}", self.score())]
+                    }
+                    _ => vec![],
+                };
+
+                for (i, line) in lines.into_iter().enumerate() {
                     ctx.print(
-                        ((WIDTH * 2 - 1) as f64 - game_over_text.len() as f64) / 2.0,
-                        (HEIGHT - 1) as f64 / 2.0,
-                        "Game Over",
-                    );
-
-                    let score_text = format!("Score: {}", self.score());
-                    ctx.print(
-                        ((WIDTH * 2 - 1) as f64 - score_text.len() as f64) / 2.0,
-                        (HEIGHT - 1) as f64 / 2.0 - 1.0,
-                        score_text,
+                        ((self.level.width * 2 - 1) as f64 - line.len() as f64) / 2.0,
+                        (self.level.height - 1) as f64 / 2.0 - i as f64,
+                        line,
                     );
                 }
             })
@@ -297,60 +765,133 @@ impl<const WIDTH: usize, const HEIGHT: usize> Game<WIDTH, HEIGHT> {
 }
 
 #[cfg(feature = "rl")]
-impl<const WIDTH: usize, const HEIGHT: usize> DiscreteActionSpace for Game<WIDTH, HEIGHT> {
+impl DiscreteActionSpace for Game {
     fn actions(&self) -> Vec<Self::Action> {
         Direction::VARIANTS.to_vec()
     }
 }
 
 #[cfg(feature = "rl")]
-impl<const WIDTH: usize, const HEIGHT: usize> Environment for Game<WIDTH, HEIGHT> {
-    type State = [f32; 5];
+impl Game {
+    fn observe(&self) -> observation::State {
+        observation::State(self.observation.encode(self))
+    }
+
+    /// Counts an action the agent picked at random, to measure how much it
+    /// explores.
+    pub(crate) fn count_random_action(&self) {
+        self.random_actions.set(self.random_actions.get() + 1);
+    }
+
+    /// Saves the replay, a failure only costing the replay and not the
+    /// whole training run.
+    fn save_replay_or_warn(&self) {
+        if let Err(err) = self.save_replay() {
+            eprintln!("Failed to save replay: {err}");
+        }
+    }
+}
+
+#[cfg(feature = "rl")]
+impl Shapes for Game {
+    const ACTION_COUNT: usize = Direction::VARIANTS.len();
+
+    fn observation_size(&self) -> usize {
+        self.observation.size(self)
+    }
+}
+
+#[cfg(feature = "rl")]
+impl Environment for Game {
+    type State = observation::State;
     type Action = Direction;
 
     fn is_active(&self) -> bool {
-        self.is_running()
+        self.episode_end().is_none()
     }
 
     fn reset(&mut self) -> Self::State {
-        let default = Self::default();
-        self.apple = default.apple;
-        self.snake = default.snake;
-        self.direction = default.direction;
-        self.state = default.state;
+        self.restart();
 
-        self.state()
+        self.observe()
     }
 
     fn random_action(&self) -> Self::Action {
-        *fastrand::choice(self.actions().iter()).unwrap()
+        self.count_random_action();
+        *self
+            .action_rng
+            .borrow_mut()
+            .choice(self.actions().iter())
+            .unwrap()
     }
 
     fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32) {
         self.report.entry("steps").and_modify(|x| *x += 1.0);
-        let mut reward = -0.01;
+        let random_actions = self.random_actions.take() as f64;
+        self.report
+            .entry("random_actions")
+            .and_modify(|x| *x += random_actions);
+        let rewards = self.rewards;
+        let distance = self.apple_distance();
+        let score = self.score();
 
         self.direction = action;
         self.step();
 
-        if self.snake.is_growing() {
+        // Components in the order of RewardConfig::REPORT_KEYS
+        let mut components = [rewards.step, 0.0, 0.0, 0.0, 0.0, 0.0];
+
+        // Still growing from the last apple when crashing is no new apple
+        if self.score() > score {
             self.report.entry("score").and_modify(|x| *x += 1.0);
-            reward += 1.0;
+            self.steps_since_apple = 0;
+            components[1] = rewards.apple;
+        } else {
+            self.steps_since_apple += 1;
+            if rewards.starvation_steps > 0
+                && self
+                    .steps_since_apple
+                    .is_multiple_of(rewards.starvation_steps)
+            {
+                components[5] = rewards.starvation;
+            }
         }
 
-        let next_state = if self.is_active() {
-            Some(self.state())
+        let exceeds =
+            |limit: Option<usize>, steps: usize| limit.is_some_and(|limit| steps >= limit);
+        if self.is_running()
+            && (exceeds(self.limits.max_steps_without_eating, self.steps_since_apple)
+                || exceeds(self.limits.max_steps, self.inputs.len()))
+        {
+            self.is_truncated = true;
+            self.report.entry("truncated").and_modify(|x| *x += 1.0);
+        }
+
+        let next_state = if self.is_running() {
+            // A new apple is further away on purpose, only shape the reward
+            // while chasing the same one
+            if !self.snake.is_growing() {
+                components[4] = rewards.distance * (distance as f64 - self.apple_distance() as f64);
+            }
+            if self.is_truncated {
+                self.save_replay_or_warn();
+            }
+            Some(self.observe())
         } else {
-            // reward += if self.snake.len() == WIDTH * HEIGHT {
-            //     1.0
-            // } else {
-            //     -10.0
-            // };
+            if self.state == GameState::Won {
+                components[3] = rewards.win;
+            } else {
+                components[2] = rewards.death;
+            }
+            self.save_replay_or_warn();
             None
         };
 
-        self.run().unwrap();
+        for (key, component) in RewardConfig::REPORT_KEYS.into_iter().zip(components) {
+            self.report.entry(key).and_modify(|x| *x += component);
+        }
 
+        let reward: f64 = components.iter().sum();
         self.report.entry("reward").and_modify(|x| *x += reward);
         (next_state, reward as f32)
     }
@@ -0,0 +1,191 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::game::Game;
+
+/// Environment variable overriding [`HighScores::default_path`].
+pub const PATH_VAR: &str = "SNAKE_HIGH_SCORES";
+
+/// A score in the [`HighScores`] table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub board: String,
+    pub score: usize,
+    pub name: String,
+}
+
+/// Best scores of every board, stored in a file as one tab separated
+/// `board score name` entry per line.
+///
+/// Scores are only compared between games on the same board, as given by
+/// [`board`](Self::board).
+#[derive(Debug, Clone, Default)]
+pub struct HighScores {
+    path: PathBuf,
+    entries: Vec<Entry>,
+}
+
+impl HighScores {
+    /// Number of scores kept per board.
+    pub const LEN: usize = 10;
+
+    /// Path of the table, from [`PATH_VAR`] or in the user data directory.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os(PATH_VAR).filter(|path| !path.is_empty()) {
+            return Some(path.into());
+        }
+
+        Some(data_dir()?.join("snake").join("high_scores.tsv"))
+    }
+
+    /// Loads the table at `path`, which is empty if the file does not exist.
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+
+        let entries = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(parse_entry)
+            .collect::<Option<_>>()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid high score table '{}'", path.display()),
+                )
+            })?;
+
+        Ok(Self { path, entries })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let content: String = self
+            .entries
+            .iter()
+            .map(|entry| format!("{}\t{}\t{}\n", entry.board, entry.score, entry.name))
+            .collect();
+
+        fs::write(&self.path, content)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Key of the board `game` is played on: its size, level and rules.
+    ///
+    /// The level is told apart by a hash of its layout, as different levels
+    /// may share a name.
+    pub fn board(game: &Game) -> String {
+        let level = game.level();
+        let mut board = format!(
+            "{}x{} {} {:016x}",
+            game.width(),
+            game.height(),
+            field(&level.name),
+            fnv1a(level.to_string().as_bytes())
+        );
+        if game.rules().wrap_around {
+            board.push_str(" wrap-around");
+        }
+        board
+    }
+
+    /// Best scores on `board`, highest first.
+    pub fn top<'a>(&'a self, board: &'a str) -> impl Iterator<Item = &'a Entry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.board == board)
+    }
+
+    /// Whether `score` would make it into the table of `board`.
+    pub fn is_record(&self, board: &str, score: usize) -> bool {
+        score > 0
+            && (self.top(board).count() < Self::LEN
+                || self.top(board).any(|entry| score > entry.score))
+    }
+
+    /// Enters `score` in the table of `board`, dropping the lowest score
+    /// beyond [`LEN`](Self::LEN).
+    ///
+    /// Tabs and line breaks in `board` and `name` are replaced by spaces.
+    pub fn insert(&mut self, board: &str, score: usize, name: &str) {
+        let board = &field(board);
+
+        // Keep entries sorted, the newest first among equal scores
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.board == *board && entry.score <= score)
+            .unwrap_or(self.entries.len());
+
+        self.entries.insert(
+            index,
+            Entry {
+                board: field(board),
+                score,
+                name: field(name),
+            },
+        );
+
+        let mut kept = 0;
+        self.entries.retain(|entry| {
+            if entry.board != *board {
+                return true;
+            }
+            kept += 1;
+            kept <= Self::LEN
+        });
+    }
+}
+
+/// `value` without the tabs and line breaks separating fields and entries.
+fn field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+/// FNV-1a hash, which unlike the std hashers is the same across releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn parse_entry(line: &str) -> Option<Entry> {
+    let mut fields = line.splitn(3, '\t');
+
+    Some(Entry {
+        board: fields.next()?.to_string(),
+        score: fields.next()?.parse().ok()?,
+        name: fields.next()?.to_string(),
+    })
+}
+
+fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(dir.into());
+    }
+
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(PathBuf::from);
+    }
+
+    let home = PathBuf::from(env::var_os("HOME")?);
+
+    if cfg!(target_os = "macos") {
+        Some(home.join("Library").join("Application Support"))
+    } else {
+        Some(home.join(".local").join("share"))
+    }
+}
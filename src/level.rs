@@ -0,0 +1,290 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(feature = "tui")]
+use ratatui::{
+    style::Color,
+    widgets::canvas::{Painter, Shape},
+};
+
+use crate::point::Point;
+use crate::snake::{Direction, Snake};
+
+const EMPTY: &str = "empty";
+
+/// Levels shipped with the game, by name.
+pub const BUILTIN: [(&str, &str); 4] = [
+    ("box", include_str!("../levels/box.txt")),
+    ("cross", include_str!("../levels/cross.txt")),
+    ("pillars", include_str!("../levels/pillars.txt")),
+    ("tunnels", include_str!("../levels/tunnels.txt")),
+];
+
+/// Starting layout of a board: its walls, where the snake starts and where
+/// the first apples are.
+///
+/// Levels are stored as text, a few `key value` lines followed by the board
+/// drawn top row first:
+///
+/// ```text
+/// ; Comments start with a semicolon
+/// name box
+/// length 3
+/// ##########
+/// #........#
+/// #.>...@..#
+/// #........#
+/// ##########
+/// ```
+///
+/// On the board `.` is an empty cell, `#` a wall, `@` an apple and one of
+/// `^>v<` the head of the snake facing that way. The snake is `length` cells
+/// long with its tail trailing straight behind the head. Apples are eaten in
+/// reading order before new ones start to spawn randomly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub walls: HashSet<Point>,
+    pub head: Point,
+    pub direction: Direction,
+    pub length: usize,
+    pub apples: Vec<Point>,
+}
+
+impl Level {
+    /// Smallest width of an [`empty`](Self::empty) level.
+    pub const MIN_WIDTH: usize = 6;
+    /// Smallest height of an [`empty`](Self::empty) level.
+    pub const MIN_HEIGHT: usize = 1;
+
+    /// Board without walls, with the snake on the left side heading right
+    /// towards the apple.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the board is smaller than [`MIN_WIDTH`](Self::MIN_WIDTH) by
+    /// [`MIN_HEIGHT`](Self::MIN_HEIGHT).
+    pub fn empty(width: usize, height: usize) -> Self {
+        assert!(
+            width >= Self::MIN_WIDTH && height >= Self::MIN_HEIGHT,
+            "board must be at least {}x{}, got {width}x{height}",
+            Self::MIN_WIDTH,
+            Self::MIN_HEIGHT,
+        );
+
+        Self {
+            name: String::from(EMPTY),
+            width,
+            height,
+            walls: HashSet::new(),
+            head: Point::new(3, (height as f64 / 2.0) as isize),
+            direction: Direction::default(),
+            length: 3,
+            apples: vec![Point::new(
+                (width as f64 * 3.0 / 4.0) as isize,
+                (height as f64 / 2.0) as isize,
+            )],
+        }
+    }
+
+    /// Whether this is the [`empty`](Self::empty) level of its size.
+    pub fn is_empty_board(&self) -> bool {
+        // Smaller boards have no empty level to build and compare with
+        self.width >= Self::MIN_WIDTH
+            && self.height >= Self::MIN_HEIGHT
+            && self.name == EMPTY
+            && *self == Self::empty(self.width, self.height)
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        BUILTIN
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, level)| level.parse().expect("invalid builtin level"))
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        (0..self.width as isize).contains(&point.x) && (0..self.height as isize).contains(&point.y)
+    }
+
+    /// Snake at the start of the level.
+    pub fn snake(&self) -> Snake {
+        Snake::new(
+            self.head,
+            self.length - 1,
+            self.direction,
+            self.width,
+            self.height,
+        )
+    }
+}
+
+#[rustfmt::skip]
+fn head_to_char(direction: Direction) -> char {
+    match direction {
+        Direction::Up    => '^',
+        Direction::Right => '>',
+        Direction::Down  => 'v',
+        Direction::Left  => '<',
+    }
+}
+
+#[rustfmt::skip]
+fn char_to_head(c: char) -> Option<Direction> {
+    match c {
+        '^' => Some(Direction::Up),
+        '>' => Some(Direction::Right),
+        'v' => Some(Direction::Down),
+        '<' => Some(Direction::Left),
+        _ => None,
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "name {}", self.name)?;
+        writeln!(f, "length {}", self.length)?;
+
+        for y in (0..self.height as isize).rev() {
+            for x in 0..self.width as isize {
+                let point = Point::new(x, y);
+                let c = if point == self.head {
+                    head_to_char(self.direction)
+                } else if self.walls.contains(&point) {
+                    '#'
+                } else if self.apples.contains(&point) {
+                    '@'
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name = String::from("custom");
+        let mut length = 3;
+        let mut rows = Vec::new();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            match line.split_once(' ') {
+                Some(_) if !rows.is_empty() => return Err(format!("invalid row '{line}'")),
+                Some(("name", value)) => name = value.trim().to_string(),
+                Some(("length", value)) => {
+                    length = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("invalid length '{value}'"))?
+                }
+                Some((key, _)) => return Err(format!("unknown field '{key}'")),
+                None => rows.push(line),
+            }
+        }
+
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err(String::from("missing board"));
+        }
+
+        let mut walls = HashSet::new();
+        let mut apples = Vec::new();
+        let mut head = None;
+
+        for (row, line) in rows.iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(format!("row {} is not {width} cells wide", row + 1));
+            }
+
+            let y = (height - 1 - row) as isize;
+            for (x, c) in line.chars().enumerate() {
+                let point = Point::new(x as isize, y);
+                match c {
+                    '.' => (),
+                    '#' => {
+                        walls.insert(point);
+                    }
+                    '@' => apples.push(point),
+                    c => match char_to_head(c) {
+                        Some(_) if head.is_some() => {
+                            return Err(String::from("more than one snake"))
+                        }
+                        Some(direction) => head = Some((point, direction)),
+                        None => return Err(format!("invalid cell '{c}'")),
+                    },
+                }
+            }
+        }
+
+        let (head, direction) = head.ok_or("missing snake")?;
+        if length == 0 {
+            return Err(String::from("length must be at least 1"));
+        }
+
+        // Checked before building the snake, which allocates all its cells
+        #[rustfmt::skip]
+        let room = match direction {
+            Direction::Up =>    head.y + 1,
+            Direction::Right => head.x + 1,
+            Direction::Down =>  height as isize - head.y,
+            Direction::Left =>  width as isize - head.x,
+        } as usize;
+        if length > room {
+            return Err(String::from("snake does not fit on the board"));
+        }
+
+        let level = Self {
+            name,
+            width,
+            height,
+            walls,
+            head,
+            direction,
+            length,
+            apples,
+        };
+
+        let snake = level.snake();
+        for point in snake.body() {
+            if !level.contains(point) || level.walls.contains(point) {
+                return Err(String::from("snake does not fit on the board"));
+            }
+            if level.apples.contains(point) {
+                return Err(String::from("apple on the snake"));
+            }
+        }
+
+        Ok(level)
+    }
+}
+
+#[cfg(feature = "tui")]
+impl Shape for Level {
+    fn draw(&self, painter: &mut Painter) {
+        self.walls.iter().for_each(|p| p.draw(painter, Color::Gray));
+    }
+}
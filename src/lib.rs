@@ -0,0 +1,52 @@
+//! Snake game engine.
+//!
+//! The [`Game`] type holds the full game rules and can be driven without any
+//! frontend: construct it, give it a [`Direction`] and [`step`](Game::step) it,
+//! then inspect its [`Snake`], [`Apple`] and [`GameState`]. Boards can have
+//! walls, described by a [`Level`].
+//!
+//! ```
+//! use snake::{Direction, Game};
+//!
+//! let mut game = Game::new(17, 15);
+//! game.set_direction(Direction::Up);
+//! game.step();
+//!
+//! assert!(game.is_running());
+//! assert_eq!(game.snake().direction(), Direction::Up);
+//! ```
+
+pub mod apple;
+#[cfg(feature = "rl")]
+pub mod env;
+pub mod free_cells;
+pub mod game;
+pub mod high_score;
+#[cfg(feature = "tui")]
+pub mod init;
+pub mod level;
+#[cfg(feature = "rl")]
+pub mod metrics;
+#[cfg(feature = "rl")]
+pub mod observation;
+pub mod point;
+#[cfg(all(feature = "tui", feature = "rl"))]
+pub mod render;
+pub mod replay;
+#[cfg(feature = "rl")]
+pub mod reward;
+pub mod rules;
+pub mod snake;
+pub mod speed;
+#[cfg(feature = "rl")]
+pub mod vec_env;
+
+pub use crate::apple::Apple;
+pub use crate::game::{Game, GameState};
+pub use crate::high_score::HighScores;
+pub use crate::level::Level;
+pub use crate::point::Point;
+pub use crate::replay::Replay;
+pub use crate::rules::Rules;
+pub use crate::snake::{Direction, Snake};
+pub use crate::speed::{Difficulty, Speed};
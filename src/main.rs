@@ -1,39 +1,80 @@
-mod apple;
-mod game;
-#[cfg(feature = "tui")]
-mod init;
+#[cfg(feature = "rl")]
+mod backend;
+#[cfg(feature = "rl")]
+mod checkpoint;
+#[cfg(any(feature = "tui", feature = "rl"))]
+mod cli;
 #[cfg(feature = "rl")]
 mod model;
-mod point;
-mod snake;
+#[cfg(all(feature = "tui", feature = "rl"))]
+mod watch;
 
 #[cfg(feature = "rl")]
-use std::sync::{LazyLock, Mutex};
-
-#[cfg(all(feature = "tui", feature = "rl"))]
-use ratatui::DefaultTerminal;
+use std::borrow::BorrowMut;
 #[cfg(feature = "tui")]
-use ratatui::{TerminalOptions, Viewport};
+use std::path::Path;
+#[cfg(feature = "rl")]
+use std::time::Instant;
 
-use crate::game::Game;
+#[cfg(feature = "ndarray")]
+use rl::burn::backend::{ndarray::NdArrayDevice, NdArray};
+#[cfg(feature = "wgpu")]
+use rl::burn::backend::{wgpu::WgpuDevice, Wgpu};
+#[cfg(feature = "rl")]
+use rl::burn::{backend::Autodiff, tensor::backend::AutodiffBackend};
 
-const GRID_HEIGHT: usize = 15;
-const GRID_WIDTH: usize = 17;
+#[cfg(feature = "tui")]
+use ratatui::{TerminalOptions, Viewport};
 
+#[cfg(feature = "rl")]
+use snake::env::{ActionSpace, EpisodeLimits, Recorded, Relative, Shapes};
+#[cfg(feature = "rl")]
+use snake::Game;
+#[cfg(all(feature = "tui", not(feature = "rl")))]
+use snake::HighScores;
 #[cfg(all(feature = "tui", feature = "rl"))]
-static TERMINAL: LazyLock<Mutex<DefaultTerminal>> = LazyLock::new(|| {
-    Mutex::new(init::init_with_options(TerminalOptions {
-        viewport: Viewport::Inline(GRID_HEIGHT as u16 + 2),
-    }))
-});
+use snake::{
+    env::Observed,
+    render::{RenderPolicy, Renderer},
+};
+#[cfg(feature = "tui")]
+use snake::{init, Replay};
+
+#[cfg(feature = "rl")]
+use crate::backend::Backend;
+#[cfg(any(feature = "tui", feature = "rl"))]
+use crate::cli::Args;
+#[cfg(feature = "rl")]
+use crate::model::LinearQNet;
 
 #[cfg(all(feature = "tui", not(feature = "rl")))]
 fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+
+    if let Some(path) = &args.replay {
+        return play_replay(path);
+    }
+
+    // A broken table is left alone rather than overwritten by new scores
+    let high_scores = args
+        .scores
+        .clone()
+        .or_else(HighScores::default_path)
+        .and_then(|path| {
+            HighScores::load(path)
+                .inspect_err(|err| eprintln!("High scores are disabled: {err}"))
+                .ok()
+        });
+
     let terminal = init::init_with_options(TerminalOptions {
-        viewport: Viewport::Inline(GRID_HEIGHT as u16 + 2),
+        viewport: Viewport::Inline(args.height as u16 + 2),
     });
 
-    let game = Game::<GRID_WIDTH, GRID_HEIGHT>::default();
+    let mut game = args.game();
+    game.set_speed(args.speed());
+    if let Some(high_scores) = high_scores {
+        game.set_high_scores(high_scores);
+    }
 
     let result = game.run(terminal);
 
@@ -43,62 +84,241 @@ fn main() -> std::io::Result<()> {
 
 #[cfg(all(not(feature = "tui"), feature = "rl"))]
 fn main() {
-    use rl::burn::backend::{wgpu::WgpuDevice, Wgpu};
+    let args = Args::parse();
 
-    use rl::algo::dqn::{DQNAgent, DQNAgentConfig};
-    use rl::burn::backend::Autodiff;
+    match args.backend {
+        #[cfg(feature = "wgpu")]
+        Backend::Wgpu => train::<Autodiff<Wgpu>>(&args, &WgpuDevice::default()),
+        #[cfg(feature = "ndarray")]
+        Backend::NdArray => train::<Autodiff<NdArray>>(&args, &NdArrayDevice::default()),
+    }
+}
 
-    use crate::model::LinearQNetConfig;
+#[cfg(all(feature = "tui", feature = "rl"))]
+fn main() -> std::io::Result<()> {
+    let args = Args::parse();
 
-    type DQNBackend = Autodiff<Wgpu>;
+    if let Some(path) = &args.replay {
+        return play_replay(path);
+    }
 
-    const NUM_EPISODES: u16 = 256;
+    if let Some(path) = &args.model {
+        return match args.backend {
+            #[cfg(feature = "wgpu")]
+            Backend::Wgpu => watch_model::<Autodiff<Wgpu>>(&args, path, &WgpuDevice::default()),
+            #[cfg(feature = "ndarray")]
+            Backend::NdArray => {
+                watch_model::<Autodiff<NdArray>>(&args, path, &NdArrayDevice::default())
+            }
+        };
+    }
 
-    static DEVICE: LazyLock<WgpuDevice> = LazyLock::new(WgpuDevice::default);
+    match args.backend {
+        #[cfg(feature = "wgpu")]
+        Backend::Wgpu => train::<Autodiff<Wgpu>>(&args, &WgpuDevice::default()),
+        #[cfg(feature = "ndarray")]
+        Backend::NdArray => train::<Autodiff<NdArray>>(&args, &NdArrayDevice::default()),
+    }
 
-    let mut env = Game::<GRID_WIDTH, GRID_HEIGHT>::default();
+    init::restore();
+    Ok(())
+}
 
-    let model = LinearQNetConfig::new(4, 256, 2).init::<DQNBackend>(&*DEVICE);
-    let config = DQNAgentConfig::default();
+#[cfg(all(feature = "tui", feature = "rl"))]
+fn watch_model<B: AutodiffBackend>(
+    args: &Args,
+    path: &Path,
+    device: &B::Device,
+) -> std::io::Result<()> {
+    let model = checkpoint::load_model::<B>(path, device)?;
+    let frame_rate = args.difficulty.frame_rate();
 
-    let mut dqn = DQNAgent::new(model, config, &*DEVICE);
+    let terminal = init::init_with_options(TerminalOptions {
+        viewport: Viewport::Inline(args.height as u16 + 2),
+    });
 
-    for _ in 0..NUM_EPISODES {
-        dqn.go(&mut env);
-        let report = env.report.take();
-        println!("{:?}", report);
-    }
+    let result = match args.actions {
+        ActionSpace::Absolute => watch::watch(&model, args.game(), frame_rate, device, terminal),
+        ActionSpace::Relative => watch::watch(
+            &model,
+            Relative::new(args.game()),
+            frame_rate,
+            device,
+            terminal,
+        ),
+    };
+
+    init::restore();
+    result
 }
 
-#[cfg(all(feature = "tui", feature = "rl"))]
-fn main() -> std::io::Result<()> {
-    use rl::burn::backend::{wgpu::WgpuDevice, Wgpu};
+#[cfg(feature = "rl")]
+fn train<B: AutodiffBackend>(args: &Args, device: &B::Device) {
+    match args.actions {
+        ActionSpace::Absolute => train_on::<B, _>(args, args.game(), device),
+        ActionSpace::Relative => train_on::<B, _>(args, Relative::new(args.game()), device),
+    }
+}
 
+#[cfg(feature = "rl")]
+fn train_on<B: AutodiffBackend, E: Shapes + BorrowMut<Game>>(
+    args: &Args,
+    env: E,
+    device: &B::Device,
+) {
     use rl::algo::dqn::{DQNAgent, DQNAgentConfig};
-    use rl::burn::backend::Autodiff;
+    use snake::env::check_shapes;
+    use snake::metrics::{EpisodeMetrics, MetricsWriter, Summary};
 
+    use crate::checkpoint::{Checkpoints, Progress};
     use crate::model::LinearQNetConfig;
 
-    type DQNBackend = Autodiff<Wgpu>;
+    // Reports would mess up games drawn inline
+    #[cfg(feature = "tui")]
+    let (mut env, quiet) = (
+        Observed::new(env, Renderer::new(args.render)),
+        args.render != RenderPolicy::Never,
+    );
+    #[cfg(not(feature = "tui"))]
+    let (mut env, quiet) = (env, false);
 
-    const NUM_EPISODES: u16 = 256;
+    check_shapes(&mut env);
 
-    static DEVICE: LazyLock<WgpuDevice> = LazyLock::new(WgpuDevice::default);
+    let config = DQNAgentConfig::default();
+    let checkpoints = args
+        .checkpoints
+        .as_ref()
+        .map(|dir| Checkpoints::new(dir, checkpoint::settings(args, &config)));
+    let (model, model_config, mut progress) = match &checkpoints {
+        Some(checkpoints) if args.resume => checkpoints.load::<B>(device).unwrap_or_else(|err| {
+            eprintln!(
+                "error: cannot resume from '{}': {err}",
+                checkpoints.dir().display()
+            );
+            std::process::exit(1);
+        }),
+        _ => {
+            let config = LinearQNetConfig::for_env(&env, 256);
+            (config.init::<B>(device), config, Progress::default())
+        }
+    };
+    model.check_shapes(&env, device);
 
-    let mut env = Game::<GRID_WIDTH, GRID_HEIGHT>::default();
+    let mut dqn = DQNAgent::new(model, config, device);
 
-    let model = LinearQNetConfig::new(5, 256, 2).init::<DQNBackend>(&*DEVICE);
-    let config = DQNAgentConfig::default();
+    // Measure the loss on the transitions of every episode
+    let mut env = Recorded::new(env);
 
-    let mut dqn = DQNAgent::new(model, config, &*DEVICE);
+    let mut metrics = args.metrics.as_ref().map(|path| {
+        MetricsWriter::open(path, args.resume).unwrap_or_else(|err| {
+            eprintln!("error: cannot open '{}': {err}", path.display());
+            std::process::exit(1);
+        })
+    });
+    let mut summary = Summary::new(100);
+    // Resumed runs carry on counting from the checkpoint
+    let elapsed = progress.elapsed;
+    let start = Instant::now();
 
-    for _ in 0..NUM_EPISODES {
+    let last_episode = progress.episode + args.episodes;
+    while progress.episode < last_episode {
+        let episode_start = Instant::now();
         dqn.go(&mut env);
-        let _ = env.report.take();
+        progress.episode += 1;
+
+        let report = BorrowMut::<Game>::borrow_mut(&mut env).report.take();
+        let mut episode = EpisodeMetrics::from_report(
+            progress.episode,
+            &report,
+            progress.steps,
+            episode_start.elapsed(),
+            elapsed + start.elapsed(),
+        );
+        episode.loss = dqn.model().td_loss::<E>(env.transitions(), device);
+        progress.steps = episode.steps;
+        progress.elapsed = episode.elapsed;
+        summary.push(episode);
+        #[cfg(feature = "tui")]
+        env.inner_mut().observer_mut().record(episode);
+
+        if let Some(metrics) = &mut metrics {
+            metrics.write(&episode).expect("failed to write metrics");
+        }
+        if !quiet {
+            println!("{}", summary.line());
+        }
+
+        let Some(checkpoints) = &checkpoints else {
+            continue;
+        };
+        if progress.episode.is_multiple_of(args.checkpoint_every)
+            || progress.episode == last_episode
+        {
+            #[cfg(feature = "tui")]
+            env.inner_mut().observer_mut().set_evaluating(true);
+            let score = evaluate(dqn.model(), &mut env, args.eval_episodes, device);
+            #[cfg(feature = "tui")]
+            env.inner_mut().observer_mut().set_evaluating(false);
+
+            checkpoints
+                .save(dqn.model(), &model_config, &mut progress, score)
+                .expect("failed to save checkpoint");
+        }
     }
+}
+
+/// Average score of `model` playing `episodes` games of `env` greedily.
+#[cfg(feature = "rl")]
+fn evaluate<B: AutodiffBackend, E: Shapes + BorrowMut<Game>>(
+    model: &LinearQNet<B>,
+    env: &mut E,
+    episodes: usize,
+    device: &B::Device,
+) -> f64 {
+    let game: &mut Game = env.borrow_mut();
+    let limits = game.limits();
+    let max_steps_without_eating = game.width() * game.height();
+
+    // A greedy agent may go round in circles forever
+    game.set_limits(EpisodeLimits {
+        max_steps_without_eating: limits
+            .max_steps_without_eating
+            .or(Some(max_steps_without_eating)),
+        ..limits
+    });
+
+    let mut total_score = 0;
+    for _ in 0..episodes {
+        let mut state = env.reset();
+        while env.is_active() {
+            let action = model.greedy_action::<E>(state.clone(), device);
+            match env.step(action) {
+                (Some(next_state), _) => state = next_state,
+                (None, _) => break,
+            }
+        }
+        total_score += BorrowMut::<Game>::borrow_mut(env).score();
+    }
+
+    let game: &mut Game = env.borrow_mut();
+    game.set_limits(limits);
+    let _ = game.report.take();
+
+    total_score as f64 / episodes.max(1) as f64
+}
+
+#[cfg(feature = "tui")]
+fn play_replay(path: &Path) -> std::io::Result<()> {
+    let replay = Replay::read(path)?;
+
+    let terminal = init::init_with_options(TerminalOptions {
+        viewport: Viewport::Inline(replay.level.height as u16 + 2),
+    });
+
+    let result = replay.play(terminal);
 
     init::restore();
-    Ok(())
+    result
 }
 
 #[cfg(not(any(feature = "tui", feature = "rl")))]
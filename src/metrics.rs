@@ -0,0 +1,237 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Statistics of one training episode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EpisodeMetrics {
+    pub episode: usize,
+    pub score: f64,
+    pub reward: f64,
+    /// Steps taken in training, including the runs it resumed from.
+    pub steps: u64,
+    /// Steps taken during the episode.
+    pub length: u64,
+    /// Share of the actions the agent picked at random, its exploration
+    /// rate as seen from the environment. `None` for empty episodes.
+    pub epsilon: Option<f64>,
+    /// Mean squared temporal difference error of the model on the steps of
+    /// the episode, once it is over. `None` if not measured.
+    pub loss: Option<f64>,
+    /// Time spent on the episode.
+    pub duration: Duration,
+    /// Time spent training, including the runs it resumed from, at the end
+    /// of the episode.
+    pub elapsed: Duration,
+}
+
+impl EpisodeMetrics {
+    /// Metrics of episode `episode` from the [`Report`](rl::env::Report) of
+    /// its game, without a [`loss`](Self::loss).
+    pub fn from_report(
+        episode: usize,
+        report: &BTreeMap<&str, f64>,
+        total_steps: u64,
+        duration: Duration,
+        elapsed: Duration,
+    ) -> Self {
+        let get = |key| report.get(key).copied();
+        let length = get("steps").unwrap_or_default() as u64;
+        let random_actions = get("random_actions").unwrap_or_default();
+
+        Self {
+            episode,
+            score: get("score").unwrap_or_default(),
+            reward: get("reward").unwrap_or_default(),
+            steps: total_steps + length,
+            length,
+            epsilon: (length > 0).then(|| random_actions / length as f64),
+            loss: None,
+            duration,
+            elapsed,
+        }
+    }
+}
+
+/// File format of the metrics, from the extension of their file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    JsonLines,
+}
+
+const FIELDS: [&str; 9] = [
+    "episode", "score", "reward", "steps", "length", "epsilon", "loss", "seconds", "elapsed",
+];
+
+/// Writes the metrics of every episode to a CSV or JSON Lines file, one
+/// line per episode.
+#[derive(Debug)]
+pub struct MetricsWriter {
+    format: Format,
+    out: BufWriter<File>,
+}
+
+impl MetricsWriter {
+    /// Opens the file at `path`, as CSV if it ends in `.csv` or JSON Lines
+    /// if it ends in `.jsonl`, appending to it when resuming training.
+    pub fn open(path: &Path, append: bool) -> io::Result<Self> {
+        let format = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => Format::Csv,
+            Some("jsonl") => Format::JsonLines,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown metrics format '{}'", path.display()),
+                ))
+            }
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)?;
+        let is_empty = file.metadata()?.len() == 0;
+
+        let mut out = BufWriter::new(file);
+        if format == Format::Csv && is_empty {
+            writeln!(out, "{}", FIELDS.join(","))?;
+        }
+
+        Ok(Self { format, out })
+    }
+
+    pub fn write(&mut self, metrics: &EpisodeMetrics) -> io::Result<()> {
+        let optional = |value: Option<f64>, missing: &str| {
+            value.map_or_else(|| missing.to_string(), |value| value.to_string())
+        };
+
+        let values = [
+            metrics.episode.to_string(),
+            metrics.score.to_string(),
+            metrics.reward.to_string(),
+            metrics.steps.to_string(),
+            metrics.length.to_string(),
+            optional(metrics.epsilon, self.missing()),
+            optional(metrics.loss, self.missing()),
+            metrics.duration.as_secs_f64().to_string(),
+            metrics.elapsed.as_secs_f64().to_string(),
+        ];
+
+        let line = match self.format {
+            Format::Csv => values.join(","),
+            Format::JsonLines => {
+                let fields: Vec<String> = FIELDS
+                    .iter()
+                    .zip(&values)
+                    .map(|(field, value)| format!("\"{field}\":{value}"))
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            }
+        };
+
+        writeln!(self.out, "{line}")?;
+        // Keep the file readable while training goes on
+        self.out.flush()
+    }
+
+    fn missing(&self) -> &'static str {
+        match self.format {
+            Format::Csv => "",
+            Format::JsonLines => "null",
+        }
+    }
+}
+
+/// Statistics over the last few episodes.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    window: usize,
+    episodes: VecDeque<EpisodeMetrics>,
+}
+
+impl Summary {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            episodes: VecDeque::with_capacity(window),
+        }
+    }
+
+    pub fn push(&mut self, metrics: EpisodeMetrics) {
+        if self.episodes.len() == self.window {
+            self.episodes.pop_front();
+        }
+        self.episodes.push_back(metrics);
+    }
+
+    /// Metrics of the last episodes, oldest first.
+    pub fn episodes(&self) -> &VecDeque<EpisodeMetrics> {
+        &self.episodes
+    }
+
+    pub fn mean_score(&self) -> f64 {
+        self.mean(|metrics| metrics.score)
+    }
+
+    pub fn max_score(&self) -> f64 {
+        self.episodes
+            .iter()
+            .map(|metrics| metrics.score)
+            .fold(0.0, f64::max)
+    }
+
+    pub fn mean_reward(&self) -> f64 {
+        self.mean(|metrics| metrics.reward)
+    }
+
+    pub fn steps_per_second(&self) -> f64 {
+        let steps: u64 = self.episodes.iter().map(|metrics| metrics.length).sum();
+        let seconds: f64 = self
+            .episodes
+            .iter()
+            .map(|metrics| metrics.duration.as_secs_f64())
+            .sum();
+
+        if seconds > 0.0 {
+            steps as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// One line of the rolling statistics, for the console.
+    pub fn line(&self) -> String {
+        let Some(last) = self.episodes.back() else {
+            return String::new();
+        };
+
+        let mut line = format!(
+            "episode {:>6}  score {:>6.2} (max {:>3})  reward {:>8.3}  {:>8.0} steps/s",
+            last.episode,
+            self.mean_score(),
+            self.max_score(),
+            self.mean_reward(),
+            self.steps_per_second(),
+        );
+        if let Some(epsilon) = last.epsilon {
+            let _ = write!(line, "  epsilon {epsilon:.3}");
+        }
+        if let Some(loss) = last.loss {
+            let _ = write!(line, "  loss {loss:.4}");
+        }
+        line
+    }
+
+    fn mean(&self, value: impl Fn(&EpisodeMetrics) -> f64) -> f64 {
+        if self.episodes.is_empty() {
+            return 0.0;
+        }
+        self.episodes.iter().map(value).sum::<f64>() / self.episodes.len() as f64
+    }
+}
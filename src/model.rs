@@ -2,10 +2,16 @@ use burn::{
     module::Param,
     nn::{Linear, LinearConfig, Relu},
     prelude::{Backend, Config, Module, Tensor},
-    tensor::backend::AutodiffBackend,
+    tensor::{backend::AutodiffBackend, TensorData},
 };
 use rl::algo::dqn::DQNModel;
 use rl::burn;
+use rl::env::Environment;
+use snake::env::{Shapes, Transition};
+
+/// Discount of future rewards in [`LinearQNet::td_loss`], the usual one for
+/// DQN.
+pub const DISCOUNT: f32 = 0.99;
 
 #[derive(Module, Debug)]
 pub struct LinearQNet<B: Backend> {
@@ -22,6 +28,12 @@ pub struct LinearQNetConfig {
 }
 
 impl LinearQNetConfig {
+    /// Network taking the observations of `env` and rating each of its
+    /// actions.
+    pub fn for_env<E: Shapes>(env: &E, hidden_size: usize) -> Self {
+        Self::new(env.observation_size(), hidden_size, E::ACTION_COUNT)
+    }
+
     pub fn init<B: Backend>(&self, device: &B::Device) -> LinearQNet<B> {
         LinearQNet {
             linear1: LinearConfig::new(self.input_size, self.hidden_size).init(device),
@@ -31,6 +43,124 @@ impl LinearQNetConfig {
     }
 }
 
+impl<B: AutodiffBackend> LinearQNet<B> {
+    /// Panics unless the network takes the observations of `env` and rates
+    /// each of its actions.
+    pub fn check_shapes<E: Shapes>(&self, env: &E, device: &B::Device) {
+        let input = Tensor::zeros([1, self.linear1.weight.dims()[0]], device);
+        let [_, output_size] = self.forward(input).dims();
+
+        assert_eq!(
+            self.linear1.weight.dims()[0],
+            env.observation_size(),
+            "model input size does not match the observation size of the environment",
+        );
+        assert_eq!(
+            output_size,
+            E::ACTION_COUNT,
+            "model output size does not match the action count of the environment",
+        );
+    }
+}
+
+impl<B: AutodiffBackend> LinearQNet<B> {
+    /// Rating of every action in `state`.
+    pub fn q_values(&self, state: impl Into<TensorData>, device: &B::Device) -> Vec<f32> {
+        let data: TensorData = state.into();
+        let input = Tensor::<B, 1>::from_data(data.convert::<f32>(), device).unsqueeze();
+
+        self.forward(input)
+            .into_data()
+            .convert::<f32>()
+            .to_vec()
+            .expect("q-values converted to f32")
+    }
+
+    /// Rating of every action in each of `states`, in a single pass.
+    fn q_value_batch<S: Into<TensorData>>(
+        &self,
+        states: impl IntoIterator<Item = S>,
+        device: &B::Device,
+    ) -> Vec<Vec<f32>> {
+        let mut values = Vec::new();
+        let mut count = 0;
+        for state in states {
+            let data: TensorData = state.into();
+            values.extend(data.convert::<f32>().iter::<f32>());
+            count += 1;
+        }
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let size = values.len() / count;
+        let input = Tensor::<B, 2>::from_data(TensorData::new(values, [count, size]), device);
+        let [_, actions] = self.linear2.weight.dims();
+
+        self.forward(input)
+            .into_data()
+            .convert::<f32>()
+            .to_vec()
+            .expect("q-values converted to f32")
+            .chunks(actions)
+            .map(<[f32]>::to_vec)
+            .collect()
+    }
+
+    /// Mean squared temporal difference error of the network on
+    /// `transitions`, bootstrapping from its own rating of the next states
+    /// with a [`DISCOUNT`]. `None` without transitions.
+    ///
+    /// The agent keeps the loss it trains on to itself, this is the same
+    /// measure taken from outside.
+    pub fn td_loss<E: Environment>(
+        &self,
+        transitions: &[Transition<E>],
+        device: &B::Device,
+    ) -> Option<f64> {
+        if transitions.is_empty() {
+            return None;
+        }
+
+        let q_values =
+            self.q_value_batch(transitions.iter().map(|(state, ..)| state.clone()), device);
+        let mut next_q_values = self
+            .q_value_batch(
+                transitions
+                    .iter()
+                    .filter_map(|(.., next_state)| next_state.clone()),
+                device,
+            )
+            .into_iter();
+
+        let total: f64 = transitions
+            .iter()
+            .zip(q_values)
+            .map(|((_, action, reward, next_state), q_values)| {
+                let mut target = *reward;
+                if next_state.is_some() {
+                    let next = next_q_values.next().expect("q-values of every next state");
+                    target += DISCOUNT * next.into_iter().fold(f32::NEG_INFINITY, f32::max);
+                }
+                let error = f64::from(q_values[action_index(action.clone())] - target);
+                error * error
+            })
+            .sum();
+
+        Some(total / transitions.len() as f64)
+    }
+
+    /// Best rated action in `state`.
+    pub fn greedy_action<E: Environment>(&self, state: E::State, device: &B::Device) -> E::Action {
+        let q_values = self.q_values(state, device);
+        let best = (0..q_values.len())
+            .max_by(|&a, &b| q_values[a].total_cmp(&q_values[b]))
+            .expect("model rates no action");
+
+        E::Action::from(best as isize)
+    }
+}
+
 impl<B: AutodiffBackend> DQNModel<B, 2> for LinearQNet<B> {
     fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
         let x = self.linear1.forward(input);
@@ -65,3 +195,13 @@ fn soft_update_linear<B: Backend>(mut this: Linear<B>, that: &Linear<B>, tau: f3
 
     this
 }
+
+/// Index of `action` among the outputs of a network, the inverse of its
+/// conversion from `isize`.
+fn action_index(action: impl Into<TensorData>) -> usize {
+    let data: TensorData = action.into();
+    data.convert::<f32>()
+        .iter::<f32>()
+        .next()
+        .expect("action has a value") as usize
+}
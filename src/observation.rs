@@ -0,0 +1,264 @@
+use std::fmt;
+
+use rl::burn::tensor::TensorData;
+
+use crate::game::Game;
+use crate::point::Point;
+use crate::snake::Direction;
+
+/// Names of the built-in encoders, as accepted by [`by_name`].
+pub const NAMES: [&str; 4] = ["distances", "danger", "grid", "window"];
+
+/// Encodes a game into the values an agent observes.
+pub trait Observation: fmt::Debug + Send {
+    /// Number of values in an encoding of `game`, which only depends on its
+    /// board.
+    fn size(&self, game: &Game) -> usize;
+
+    fn encode(&self, game: &Game) -> Vec<f32>;
+}
+
+/// Built-in encoder called `name`, with its default settings.
+pub fn by_name(name: &str) -> Option<Box<dyn Observation>> {
+    match name {
+        "distances" => Some(Box::new(Distances)),
+        "danger" => Some(Box::new(Danger)),
+        "grid" => Some(Box::new(Grid)),
+        "window" => Some(Box::new(Window::default())),
+        _ => None,
+    }
+}
+
+/// An encoded observation, as fed to a model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct State(pub Vec<f32>);
+
+impl From<State> for TensorData {
+    fn from(value: State) -> Self {
+        let len = value.0.len();
+        TensorData::new(value.0, vec![len])
+    }
+}
+
+/// Distance to the apple and to the nearest obstacle on each side of the
+/// head, ignoring the snake.
+///
+/// The apple distance is first, followed by the obstacles right, left, up
+/// and down.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Distances;
+
+impl Observation for Distances {
+    fn size(&self, _game: &Game) -> usize {
+        5
+    }
+
+    fn encode(&self, game: &Game) -> Vec<f32> {
+        let (dx, dy) = game.apple_offset();
+        let apple_distance = dx.unsigned_abs() + dy.unsigned_abs();
+
+        let mut values = vec![apple_distance as f32];
+        for direction in [
+            Direction::Right,
+            Direction::Left,
+            Direction::Up,
+            Direction::Down,
+        ] {
+            values.push(distance_to_obstacle(game, direction) as f32);
+        }
+        values
+    }
+}
+
+/// Whether moving straight, right or left kills the snake, which way it is
+/// heading and which way the apple is.
+///
+/// The three dangers come first, then the heading one-hot and the apple
+/// being up, right, down or left of the head, all in [`Direction::VARIANTS`]
+/// order.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Danger;
+
+impl Observation for Danger {
+    fn size(&self, _game: &Game) -> usize {
+        11
+    }
+
+    fn encode(&self, game: &Game) -> Vec<f32> {
+        let head = *game.snake().head();
+        let heading = game.snake().direction();
+        let (dx, dy) = game.apple_offset();
+
+        let dangers = [heading, heading.clockwise(), heading.counter_clockwise()]
+            .map(|direction| is_deadly(game, &neighbour(game, head, direction)));
+
+        let apple = Direction::VARIANTS.map(|direction| match direction {
+            Direction::Up => dy > 0,
+            Direction::Right => dx > 0,
+            Direction::Down => dy < 0,
+            Direction::Left => dx < 0,
+        });
+
+        dangers
+            .into_iter()
+            .chain(Direction::VARIANTS.map(|direction| direction == heading))
+            .chain(apple)
+            .map(f32::from)
+            .collect()
+    }
+}
+
+/// The whole board as one-hot channels for the head, the rest of the body,
+/// the apple and the walls.
+///
+/// Each channel lists the board row by row from the bottom left, one channel
+/// after the other.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Grid;
+
+impl Grid {
+    const CHANNELS: usize = 4;
+}
+
+impl Observation for Grid {
+    fn size(&self, game: &Game) -> usize {
+        Self::CHANNELS * game.width() * game.height()
+    }
+
+    fn encode(&self, game: &Game) -> Vec<f32> {
+        let cells = game.width() * game.height();
+        let mut values = vec![0.0; self.size(game)];
+        let mut set = |channel: usize, point: &Point| {
+            let index = point.y as usize * game.width() + point.x as usize;
+            values[channel * cells + index] = 1.0;
+        };
+
+        let mut body = game.snake().body().into_iter();
+        if let Some(head) = body.next() {
+            set(0, head);
+        }
+        body.for_each(|point| set(1, point));
+        set(2, game.apple().position());
+        game.level().walls.iter().for_each(|point| set(3, point));
+
+        values
+    }
+}
+
+/// Square of cells around the head, turned so the snake always faces up,
+/// as one-hot channels for the body, the apple and obstacles.
+///
+/// Cells off the board are obstacles unless the game wraps around. Each
+/// channel lists the window row by row from the back left, one channel after
+/// the other.
+#[derive(Debug, Clone, Copy)]
+pub struct Window {
+    /// Number of cells seen on each side of the head.
+    pub radius: usize,
+}
+
+impl Window {
+    const CHANNELS: usize = 3;
+
+    fn side(&self) -> usize {
+        2 * self.radius + 1
+    }
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self { radius: 3 }
+    }
+}
+
+impl Observation for Window {
+    fn size(&self, _game: &Game) -> usize {
+        Self::CHANNELS * self.side() * self.side()
+    }
+
+    fn encode(&self, game: &Game) -> Vec<f32> {
+        let side = self.side();
+        let cells = side * side;
+        let mut values = vec![0.0; Self::CHANNELS * cells];
+
+        let head = *game.snake().head();
+        let forward = game.snake().direction();
+        let right = forward.clockwise();
+        let radius = self.radius as isize;
+
+        for row in 0..side {
+            for column in 0..side {
+                let mut point = head;
+                point = walk(point, forward, row as isize - radius);
+                point = walk(point, right, column as isize - radius);
+                if game.rules().wrap_around {
+                    point = point.wrapped(game.width(), game.height());
+                }
+
+                let index = row * side + column;
+                if game.snake().contains(&point) {
+                    values[index] = 1.0;
+                }
+                if game.apple().position() == &point {
+                    values[cells + index] = 1.0;
+                }
+                if is_blocked(game, &point) {
+                    values[2 * cells + index] = 1.0;
+                }
+            }
+        }
+
+        values
+    }
+}
+
+/// Moves `steps` cells in `direction`, or backwards if negative.
+fn walk(point: Point, direction: Direction, steps: isize) -> Point {
+    let direction = if steps < 0 {
+        direction.opposite()
+    } else {
+        direction
+    };
+    (0..steps.unsigned_abs()).fold(point, |point, _| point.moved(direction))
+}
+
+fn neighbour(game: &Game, point: Point, direction: Direction) -> Point {
+    let point = point.moved(direction);
+    if game.rules().wrap_around {
+        point.wrapped(game.width(), game.height())
+    } else {
+        point
+    }
+}
+
+/// Whether `point` is off the board or a wall.
+fn is_blocked(game: &Game, point: &Point) -> bool {
+    !game.level().contains(point) || game.level().walls.contains(point)
+}
+
+/// Whether moving the head onto `point` ends the game.
+fn is_deadly(game: &Game, point: &Point) -> bool {
+    let snake = game.snake();
+    // The tail moves out of the way, unless the snake grows
+    is_blocked(game, point)
+        || (snake.contains(point) && (snake.is_growing() || point != snake.tail()))
+}
+
+/// Number of cells from the head to the first wall or edge in `direction`,
+/// capped at the board size when wrapping around.
+fn distance_to_obstacle(game: &Game, direction: Direction) -> usize {
+    let limit = match direction {
+        Direction::Up | Direction::Down => game.height(),
+        Direction::Right | Direction::Left => game.width(),
+    };
+
+    let mut point = *game.snake().head();
+    for distance in 1..limit {
+        point = neighbour(game, point, direction);
+        if is_blocked(game, &point) {
+            return distance;
+        }
+    }
+
+    limit
+}
@@ -4,6 +4,9 @@ use ratatui::{
     widgets::canvas::{Painter, Points, Shape},
 };
 
+use crate::snake::Direction;
+
+/// A cell on the board, with `(0, 0)` at the bottom left.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Point {
     pub x: isize,
@@ -14,6 +17,26 @@ impl Point {
     pub fn new(x: isize, y: isize) -> Self {
         Self { x, y }
     }
+
+    /// Neighbouring point in `direction`.
+    #[rustfmt::skip]
+    pub fn moved(self, direction: Direction) -> Self {
+        match direction {
+            Direction::Up =>    Point::new(self.x    , self.y + 1),
+            Direction::Right => Point::new(self.x + 1, self.y    ),
+            Direction::Down =>  Point::new(self.x    , self.y - 1),
+            Direction::Left =>  Point::new(self.x - 1, self.y    ),
+        }
+    }
+
+    /// Brings the point back onto a `width` by `height` board, as if its
+    /// opposite edges were joined.
+    pub fn wrapped(self, width: usize, height: usize) -> Self {
+        Self {
+            x: self.x.rem_euclid(width as isize),
+            y: self.y.rem_euclid(height as isize),
+        }
+    }
 }
 
 #[cfg(feature = "tui")]
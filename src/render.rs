@@ -0,0 +1,331 @@
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    prelude::*,
+    symbols,
+    widgets::{Axis, Chart, Dataset, GraphType, Paragraph, Sparkline},
+    DefaultTerminal, TerminalOptions, Viewport,
+};
+
+use crate::env::Observer;
+use crate::game::Game;
+use crate::metrics::{EpisodeMetrics, Summary};
+
+/// Rows the training dashboard needs.
+const DASHBOARD_HEIGHT: u16 = 16;
+
+/// Which steps of training are drawn in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderPolicy {
+    /// Nothing, the terminal is left alone.
+    Never,
+    /// One step out of every `n`.
+    Steps(usize),
+    /// Every step of one episode out of every `n`.
+    Episodes(usize),
+    /// Every step of the episodes marked as evaluation with
+    /// [`Renderer::set_evaluating`].
+    Evaluation,
+}
+
+impl Default for RenderPolicy {
+    fn default() -> Self {
+        RenderPolicy::Steps(1)
+    }
+}
+
+impl fmt::Display for RenderPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderPolicy::Never => write!(f, "never"),
+            RenderPolicy::Steps(n) => write!(f, "steps:{n}"),
+            RenderPolicy::Episodes(n) => write!(f, "episodes:{n}"),
+            RenderPolicy::Evaluation => write!(f, "eval"),
+        }
+    }
+}
+
+impl FromStr for RenderPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_every = |n: &str| match n.parse() {
+            Ok(0) | Err(_) => Err(format!("invalid render period '{n}'")),
+            Ok(n) => Ok(n),
+        };
+
+        match s.split_once(':') {
+            None if s == "never" => Ok(RenderPolicy::Never),
+            None if s == "eval" => Ok(RenderPolicy::Evaluation),
+            Some(("steps", n)) => Ok(RenderPolicy::Steps(parse_every(n)?)),
+            Some(("episodes", n)) => Ok(RenderPolicy::Episodes(parse_every(n)?)),
+            _ => Err(format!("unknown render policy '{s}'")),
+        }
+    }
+}
+
+/// Draws the games of an agent in the terminal, following a
+/// [`RenderPolicy`], next to a dashboard of the episodes
+/// [recorded](Renderer::record) so far.
+///
+/// Unless the policy is [`RenderPolicy::Never`], the dashboard is also
+/// refreshed on its own as episodes are recorded, at most every
+/// [`DASHBOARD_REFRESH`], next to the last board drawn. The terminal is only
+/// set up on the first frame drawn.
+#[derive(Debug)]
+pub struct Renderer {
+    policy: RenderPolicy,
+    terminal: Option<DefaultTerminal>,
+    steps: usize,
+    episodes: usize,
+    is_evaluating: bool,
+    summary: Summary,
+    /// Size of the board played on, to lay out the terminal.
+    board_size: Option<(usize, usize)>,
+    /// Board last drawn, kept to refresh the dashboard next to it.
+    board: Option<Buffer>,
+    last_frame: Option<Instant>,
+}
+
+/// Shortest time between two refreshes of the dashboard alone.
+pub const DASHBOARD_REFRESH: Duration = Duration::from_millis(250);
+
+impl Renderer {
+    pub fn new(policy: RenderPolicy) -> Self {
+        Self {
+            policy,
+            terminal: None,
+            steps: 0,
+            episodes: 0,
+            is_evaluating: false,
+            summary: Summary::new(100),
+            board_size: None,
+            board: None,
+            last_frame: None,
+        }
+    }
+
+    /// Adds the metrics of a finished episode to the dashboard.
+    pub fn record(&mut self, metrics: EpisodeMetrics) {
+        self.summary.push(metrics);
+
+        // The policy may not draw a board for a long while
+        if self.policy != RenderPolicy::Never
+            && self
+                .last_frame
+                .is_none_or(|last_frame| last_frame.elapsed() >= DASHBOARD_REFRESH)
+        {
+            self.draw_frame(None).expect("failed to draw the dashboard");
+        }
+    }
+
+    /// Marks the next episodes as evaluation ones, or not.
+    pub fn set_evaluating(&mut self, is_evaluating: bool) {
+        self.is_evaluating = is_evaluating;
+    }
+
+    fn should_draw(&self) -> bool {
+        match self.policy {
+            RenderPolicy::Never => false,
+            RenderPolicy::Steps(n) => self.steps.is_multiple_of(n),
+            RenderPolicy::Episodes(n) => self.episodes.is_multiple_of(n),
+            RenderPolicy::Evaluation => self.is_evaluating,
+        }
+    }
+
+    pub fn draw(&mut self, game: &Game) -> io::Result<()> {
+        self.board_size = Some((game.width(), game.height()));
+        self.draw_frame(Some(game))
+    }
+
+    /// Draws `game` and the dashboard, or the dashboard next to the last
+    /// board drawn.
+    fn draw_frame(&mut self, game: Option<&Game>) -> io::Result<()> {
+        let Some((width, height)) = self.board_size else {
+            return Ok(());
+        };
+
+        let terminal = self.terminal.get_or_insert_with(|| {
+            crate::init::init_with_options(TerminalOptions {
+                viewport: Viewport::Inline((height as u16 + 2).max(DASHBOARD_HEIGHT)),
+            })
+        });
+
+        let dashboard = Dashboard {
+            summary: &self.summary,
+        };
+        let last_board = &mut self.board;
+        terminal.draw(|frame| {
+            let [board, side] = Layout::horizontal([
+                Constraint::Length((width * 2 + 2) as u16),
+                Constraint::Min(0),
+            ])
+            .areas(frame.area());
+
+            match game {
+                Some(game) => {
+                    frame.render_widget(game, board);
+                    *last_board = Some(copy_area(frame.buffer_mut(), board));
+                }
+                None => {
+                    if let Some(last_board) = last_board {
+                        frame.buffer_mut().merge(last_board);
+                    }
+                }
+            }
+            frame.render_widget(&dashboard, side);
+        })?;
+        self.last_frame = Some(Instant::now());
+
+        // Reset terminal cursor at the end of viewport
+        let area = terminal.get_frame().area();
+        terminal.set_cursor_position((0, area.height + area.y + 1))?;
+
+        Ok(())
+    }
+}
+
+impl Observer for Renderer {
+    fn step(&mut self, game: &Game) {
+        self.board_size = Some((game.width(), game.height()));
+        if self.should_draw() {
+            self.draw(game).expect("failed to draw the game");
+        }
+
+        self.steps += 1;
+        if game.episode_end().is_some() {
+            self.episodes += 1;
+        }
+    }
+}
+
+/// Copy of the cells of `buffer` in `area`.
+fn copy_area(buffer: &Buffer, area: Rect) -> Buffer {
+    let mut copy = Buffer::empty(area);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            copy[(x, y)] = buffer[(x, y)].clone();
+        }
+    }
+    copy
+}
+
+/// Rolling statistics of training, drawn on the side of the board.
+struct Dashboard<'a> {
+    summary: &'a Summary,
+}
+
+impl Widget for &Dashboard<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [stats, rewards, losses] = Layout::vertical([
+            Constraint::Length(7),
+            Constraint::Length(4),
+            Constraint::Min(0),
+        ])
+        .areas(area.inner(Margin::new(1, 0)));
+
+        self.render_stats(stats, buf);
+        self.render_rewards(rewards, buf);
+        self.render_losses(losses, buf);
+    }
+}
+
+impl Dashboard<'_> {
+    fn render_stats(&self, area: Rect, buf: &mut Buffer) {
+        let summary = self.summary;
+        let last = summary.episodes().back();
+        let or_none = |value: Option<f64>, precision: usize| {
+            value.map_or_else(|| String::from("-"), |value| format!("{value:.precision$}"))
+        };
+
+        Paragraph::new(vec![
+            Line::from("Training").bold(),
+            Line::from(format!(
+                "Episode  {}",
+                last.map_or(0, |metrics| metrics.episode)
+            )),
+            Line::from(format!(
+                "Score    {:.2} (max {})",
+                summary.mean_score(),
+                summary.max_score()
+            )),
+            Line::from(format!(
+                "Epsilon  {}",
+                or_none(last.and_then(|metrics| metrics.epsilon), 3)
+            )),
+            Line::from(format!(
+                "Loss     {}",
+                or_none(last.and_then(|metrics| metrics.loss), 4)
+            )),
+            Line::from(format!("Steps/s  {:.0}", summary.steps_per_second())),
+        ])
+        .render(area, buf);
+    }
+
+    fn render_rewards(&self, area: Rect, buf: &mut Buffer) {
+        let [title, sparkline] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+
+        let rewards: Vec<f64> = self
+            .summary
+            .episodes()
+            .iter()
+            .rev()
+            .take(sparkline.width as usize)
+            .rev()
+            .map(|metrics| metrics.reward)
+            .collect();
+
+        // Sparklines only take positive bars, so rewards are scaled between
+        // their bounds
+        let min = rewards.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = rewards.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let bars: Vec<u64> = rewards
+            .iter()
+            .map(|reward| match max - min {
+                range if range > 0.0 => (1.0 + 99.0 * (reward - min) / range) as u64,
+                _ => 50,
+            })
+            .collect();
+
+        Line::from(format!("Reward   {:.3}", self.summary.mean_reward())).render(title, buf);
+        Sparkline::default()
+            .data(&bars)
+            .max(100)
+            .render(sparkline, buf);
+    }
+
+    fn render_losses(&self, area: Rect, buf: &mut Buffer) {
+        let losses: Vec<(f64, f64)> = self
+            .summary
+            .episodes()
+            .iter()
+            .filter_map(|metrics| Some((metrics.episode as f64, metrics.loss?)))
+            .collect();
+
+        let Some((&(first, _), &(last, _))) = losses.first().zip(losses.last()) else {
+            return;
+        };
+        let max = losses.iter().map(|&(_, loss)| loss).fold(0.0, f64::max);
+        // A flat zero loss still gets an axis
+        let top = if max > 0.0 { max } else { 1.0 };
+
+        let dataset = Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .data(&losses);
+
+        Chart::new(vec![dataset])
+            .x_axis(Axis::default().bounds([first, last.max(first + 1.0)]))
+            .y_axis(
+                Axis::default()
+                    .title("Loss")
+                    .bounds([0.0, top])
+                    .labels([String::from("0"), format!("{top:.3}")]),
+            )
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,343 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(feature = "tui")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tui")]
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    prelude::*,
+    widgets::{Paragraph, Widget},
+};
+
+use crate::game::Game;
+use crate::level::Level;
+use crate::rules::Rules;
+use crate::snake::Direction;
+
+const HEADER: &str = "snake-replay 1";
+
+/// Everything needed to replay a game: the level, the rules, the seed and the
+/// direction given on every tick.
+///
+/// Replays are stored as text:
+///
+/// ```text
+/// snake-replay 1
+/// size 17 15
+/// rules wrap-around
+/// seed 42
+/// score 3
+/// inputs RRRUUULLD
+/// ```
+///
+/// Games not played on an [`empty`](Level::empty) board end with a `level`
+/// line followed by the [`Level`] itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub level: Level,
+    pub rules: Rules,
+    pub seed: u64,
+    pub score: usize,
+    pub inputs: Vec<Direction>,
+}
+
+impl Replay {
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Re-simulates the game up to `tick`.
+    pub fn game_at(&self, tick: usize) -> Game {
+        let mut game = Game::from_level(self.level.clone(), self.seed);
+        game.set_rules(self.rules);
+
+        for &direction in self.inputs.iter().take(tick) {
+            game.set_direction(direction);
+            game.step();
+        }
+
+        game
+    }
+
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
+#[rustfmt::skip]
+fn direction_to_char(direction: Direction) -> char {
+    match direction {
+        Direction::Up    => 'U',
+        Direction::Right => 'R',
+        Direction::Down  => 'D',
+        Direction::Left  => 'L',
+    }
+}
+
+#[rustfmt::skip]
+fn char_to_direction(c: char) -> Option<Direction> {
+    match c {
+        'U' => Some(Direction::Up),
+        'R' => Some(Direction::Right),
+        'D' => Some(Direction::Down),
+        'L' => Some(Direction::Left),
+        _ => None,
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        writeln!(f, "size {} {}", self.level.width, self.level.height)?;
+        write!(f, "rules")?;
+        if self.rules.wrap_around {
+            write!(f, " wrap-around")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "score {}", self.score)?;
+        let inputs: String = self.inputs.iter().copied().map(direction_to_char).collect();
+        writeln!(f, "inputs {inputs}")?;
+
+        if !self.level.is_empty_board() {
+            writeln!(f, "level")?;
+            write!(f, "{}", self.level)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Replay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err(format!("missing '{HEADER}' header"));
+        }
+
+        let mut size = None;
+        let mut rules = Rules::default();
+        let mut seed = None;
+        let mut score = None;
+        let mut inputs = None;
+        let mut level = None;
+
+        while let Some(line) = lines.next().map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "size" => {
+                    let (width, height) = value
+                        .split_once(' ')
+                        .ok_or_else(|| format!("invalid size '{value}'"))?;
+                    size = Some((parse_field(key, width)?, parse_field(key, height)?));
+                }
+                "rules" => {
+                    for rule in value.split_whitespace() {
+                        match rule {
+                            "wrap-around" => rules.wrap_around = true,
+                            _ => return Err(format!("unknown rule '{rule}'")),
+                        }
+                    }
+                }
+                "seed" => seed = Some(parse_field(key, value)?),
+                "score" => score = Some(parse_field(key, value)?),
+                "inputs" => {
+                    inputs = Some(
+                        value
+                            .chars()
+                            .map(|c| char_to_direction(c).ok_or(format!("invalid input '{c}'")))
+                            .collect::<Result<Vec<_>, _>>()?,
+                    )
+                }
+                "level" => {
+                    let rest: Vec<&str> = lines.by_ref().collect();
+                    level = Some(rest.join("\n").parse::<Level>()?);
+                }
+                _ => return Err(format!("unknown field '{key}'")),
+            }
+        }
+
+        let (width, height) = size.ok_or("missing 'size' field")?;
+        let level = match level {
+            Some(level) if (level.width, level.height) != (width, height) => {
+                return Err(format!("level is not {width}x{height}"));
+            }
+            Some(level) => level,
+            None if width < Game::MIN_WIDTH || height < Game::MIN_HEIGHT => {
+                return Err(format!("invalid size '{width} {height}'"));
+            }
+            None => Level::empty(width, height),
+        };
+
+        Ok(Self {
+            level,
+            rules,
+            seed: seed.ok_or("missing 'seed' field")?,
+            score: score.ok_or("missing 'score' field")?,
+            inputs: inputs.unwrap_or_default(),
+        })
+    }
+}
+
+fn parse_field<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {key} '{value}'"))
+}
+
+#[cfg(feature = "tui")]
+impl Replay {
+    /// Plays the replay back in the terminal.
+    ///
+    /// Space pauses, the left and right arrows step one tick, page up and
+    /// page down seek ten ticks, home and end jump to either end.
+    pub fn play<B: Backend>(&self, mut terminal: Terminal<B>) -> io::Result<()> {
+        let mut player = Player {
+            replay: self,
+            game: self.game_at(0),
+            tick: 0,
+            frame_rate: 10.0,
+            is_playing: true,
+        };
+
+        terminal.draw(|frame| frame.render_widget(&player, frame.area()))?;
+
+        'play: loop {
+            let now = Instant::now();
+            let timeout = Duration::from_secs_f64(1.0 / player.frame_rate);
+            let mut elapsed = now.elapsed();
+
+            while elapsed < timeout {
+                if event::poll(timeout - elapsed)? {
+                    if !player.handle_events()? {
+                        break 'play;
+                    }
+                    terminal.draw(|frame| frame.render_widget(&player, frame.area()))?;
+                }
+                elapsed = now.elapsed();
+            }
+
+            if player.is_playing {
+                player.seek(player.tick + 1);
+                if player.tick == self.len() {
+                    player.is_playing = false;
+                }
+            }
+
+            terminal.draw(|frame| frame.render_widget(&player, frame.area()))?;
+        }
+
+        // Reset terminal cursor at the end of viewport
+        let area = terminal.get_frame().area();
+        terminal.set_cursor_position((0, area.height + area.y + 1))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tui")]
+struct Player<'a> {
+    replay: &'a Replay,
+    game: Game,
+    tick: usize,
+    frame_rate: f64,
+    is_playing: bool,
+}
+
+#[cfg(feature = "tui")]
+impl Player<'_> {
+    fn seek(&mut self, tick: usize) {
+        let tick = tick.min(self.replay.len());
+
+        if tick < self.tick {
+            self.game = self.replay.game_at(0);
+            self.tick = 0;
+        }
+
+        for &direction in &self.replay.inputs[self.tick..tick] {
+            self.game.set_direction(direction);
+            self.game.step();
+        }
+        self.tick = tick;
+    }
+
+    fn pause_and_seek(&mut self, tick: usize) {
+        self.is_playing = false;
+        self.seek(tick);
+    }
+
+    /// Returns `false` once the player asked to quit.
+    fn handle_events(&mut self) -> io::Result<bool> {
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                return Ok(true);
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                KeyCode::Char(' ') => self.is_playing = !self.is_playing,
+                KeyCode::Right => self.pause_and_seek(self.tick + 1),
+                KeyCode::Left => self.pause_and_seek(self.tick.saturating_sub(1)),
+                KeyCode::PageDown => self.seek(self.tick + 10),
+                KeyCode::PageUp => self.seek(self.tick.saturating_sub(10)),
+                KeyCode::Home => self.seek(0),
+                KeyCode::End => self.seek(self.replay.len()),
+                _ => (),
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(feature = "tui")]
+impl Widget for &Player<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let [board, status] = Layout::horizontal([
+            Constraint::Length((self.game.width() * 2 + 2) as u16),
+            Constraint::Min(0),
+        ])
+        .areas(area);
+
+        self.game.render(board, buf);
+
+        Paragraph::new(vec![
+            Line::from(format!(" Tick {}/{}", self.tick, self.replay.len())),
+            Line::from(format!(" Score {}", self.game.score())),
+            Line::from(if self.is_playing {
+                " Playing"
+            } else {
+                " Paused"
+            }),
+            Line::from(""),
+            Line::from(" space  play/pause").dark_gray(),
+            Line::from(" ←/→    step").dark_gray(),
+            Line::from(" PgUp/PgDn  seek").dark_gray(),
+            Line::from(" q      quit").dark_gray(),
+        ])
+        .render(status, buf);
+    }
+}
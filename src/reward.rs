@@ -0,0 +1,133 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Rewards given to an agent on every step, summed together.
+///
+/// Configurations are stored as `key value` lines, leaving out the keys
+/// keeping their default:
+///
+/// ```text
+/// ; Comments start with a semicolon
+/// step -0.01
+/// apple 1
+/// death -10
+/// starvation-steps 200
+/// starvation -1
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RewardConfig {
+    /// Given on every step, usually negative to hurry the snake.
+    pub step: f64,
+    /// Given when eating an apple.
+    pub apple: f64,
+    /// Given when the snake crashes, usually negative.
+    pub death: f64,
+    /// Given when the snake fills the board.
+    pub win: f64,
+    /// Given per cell the snake gets closer to the apple, and taken back per
+    /// cell it moves away.
+    pub distance: f64,
+    /// Given every [`starvation_steps`](Self::starvation_steps) steps without
+    /// eating, usually negative.
+    pub starvation: f64,
+    /// Steps without eating before [`starvation`](Self::starvation) is given,
+    /// never if 0.
+    pub starvation_steps: usize,
+}
+
+impl RewardConfig {
+    /// Keys of the components in the [`Report`](rl::env::Report), in the
+    /// order of the fields.
+    pub const REPORT_KEYS: [&'static str; 6] = [
+        "step_reward",
+        "apple_reward",
+        "death_reward",
+        "win_reward",
+        "distance_reward",
+        "starvation_reward",
+    ];
+
+    /// Sets the field stored under `key` to `value`.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "step" => self.step = parse_reward(key, value)?,
+            "apple" => self.apple = parse_reward(key, value)?,
+            "death" => self.death = parse_reward(key, value)?,
+            "win" => self.win = parse_reward(key, value)?,
+            "distance" => self.distance = parse_reward(key, value)?,
+            "starvation" => self.starvation = parse_reward(key, value)?,
+            "starvation-steps" => self.starvation_steps = parse_field(key, value)?,
+            _ => return Err(format!("unknown field '{key}'")),
+        }
+        Ok(())
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        Self {
+            step: -0.01,
+            apple: 1.0,
+            death: 0.0,
+            win: 0.0,
+            distance: 0.0,
+            starvation: 0.0,
+            starvation_steps: 0,
+        }
+    }
+}
+
+impl fmt::Display for RewardConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "step {}", self.step)?;
+        writeln!(f, "apple {}", self.apple)?;
+        writeln!(f, "death {}", self.death)?;
+        writeln!(f, "win {}", self.win)?;
+        writeln!(f, "distance {}", self.distance)?;
+        writeln!(f, "starvation {}", self.starvation)?;
+        writeln!(f, "starvation-steps {}", self.starvation_steps)
+    }
+}
+
+impl FromStr for RewardConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Self::default();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once(' ')
+                .ok_or_else(|| format!("missing value for '{line}'"))?;
+            config.set(key, value.trim())?;
+        }
+
+        Ok(config)
+    }
+}
+
+/// Rewards must be finite for the returns to be.
+fn parse_reward(key: &str, value: &str) -> Result<f64, String> {
+    Some(parse_field::<f64>(key, value)?)
+        .filter(|reward| reward.is_finite())
+        .ok_or_else(|| format!("invalid {key} '{value}'"))
+}
+
+fn parse_field<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {key} '{value}'"))
+}
@@ -0,0 +1,7 @@
+/// Optional rules changing how a [`Game`](crate::Game) is played.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rules {
+    /// The snake leaving the board through one edge comes back through the
+    /// opposite one instead of dying.
+    pub wrap_around: bool,
+}
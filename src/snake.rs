@@ -5,18 +5,23 @@ use ratatui::{
     style::Color,
     widgets::canvas::{Painter, Shape},
 };
+#[cfg(feature = "rl")]
 use rl::burn::tensor::TensorData;
 
 use crate::point::Point;
 
+/// The snake, stored head first.
 #[derive(Debug, Clone)]
 pub struct Snake {
     body: VecDeque<Point>,
+    occupancy: Occupancy,
     direction: Direction,
     is_growing: bool,
     is_dead: bool,
+    wrap: Option<(usize, usize)>,
 }
 
+/// A direction on the board, with `Up` towards increasing `y`.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Up = 0,
@@ -33,11 +38,38 @@ impl Direction {
         Direction::Down,
         Direction::Left,
     ];
+
+    #[rustfmt::skip]
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up    => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down  => Direction::Up,
+            Direction::Left  => Direction::Right,
+        }
+    }
+
+    /// Direction after a right turn.
+    #[rustfmt::skip]
+    pub fn clockwise(self) -> Self {
+        match self {
+            Direction::Up    => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down  => Direction::Left,
+            Direction::Left  => Direction::Up,
+        }
+    }
+
+    /// Direction after a left turn.
+    pub fn counter_clockwise(self) -> Self {
+        self.clockwise().opposite()
+    }
 }
 
-impl Into<TensorData> for Direction {
-    fn into(self) -> TensorData {
-        TensorData::new(vec![self as u8], vec![1])
+#[cfg(feature = "rl")]
+impl From<Direction> for TensorData {
+    fn from(value: Direction) -> Self {
+        TensorData::new(vec![value as u8], vec![1])
     }
 }
 
@@ -53,11 +85,78 @@ impl From<isize> for Direction {
     }
 }
 
+/// Number of body parts on every cell of the board, to tell whether the
+/// snake covers a cell in constant time.
+///
+/// Cells off the board are only expected while validating levels and are
+/// searched linearly.
+#[derive(Debug, Clone)]
+struct Occupancy {
+    width: usize,
+    height: usize,
+    counts: Vec<u16>,
+    outside: Vec<Point>,
+}
+
+impl Occupancy {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            counts: vec![0; width * height],
+            outside: Vec::new(),
+        }
+    }
+
+    fn index(&self, point: &Point) -> Option<usize> {
+        let (x, y) = (
+            usize::try_from(point.x).ok()?,
+            usize::try_from(point.y).ok()?,
+        );
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    fn contains(&self, point: &Point) -> bool {
+        match self.index(point) {
+            Some(index) => self.counts[index] > 0,
+            None => self.outside.contains(point),
+        }
+    }
+
+    fn insert(&mut self, point: Point) {
+        match self.index(&point) {
+            Some(index) => self.counts[index] += 1,
+            None => self.outside.push(point),
+        }
+    }
+
+    fn remove(&mut self, point: &Point) {
+        match self.index(point) {
+            Some(index) => self.counts[index] -= 1,
+            None => {
+                if let Some(position) = self.outside.iter().position(|p| p == point) {
+                    self.outside.swap_remove(position);
+                }
+            }
+        }
+    }
+}
+
 impl Snake {
-    pub fn new(head: Point, tail_length: usize, direction: Direction) -> Self {
+    /// Creates a straight snake of `tail_length + 1` cells with its tail
+    /// trailing behind `head`, on a `width` by `height` board.
+    pub fn new(
+        head: Point,
+        tail_length: usize,
+        direction: Direction,
+        width: usize,
+        height: usize,
+    ) -> Self {
         let mut body = VecDeque::with_capacity(tail_length + 1);
+        let mut occupancy = Occupancy::new(width, height);
 
         body.push_front(head);
+        occupancy.insert(head);
 
         for i in 1..tail_length as isize + 1 {
             #[rustfmt::skip]
@@ -68,13 +167,16 @@ impl Snake {
                 Direction::Left =>  Point::new(head.x + i, head.y    ),
             };
             body.push_back(point);
+            occupancy.insert(point);
         }
 
         Self {
             body,
+            occupancy,
             direction,
             is_growing: false,
             is_dead: false,
+            wrap: None,
         }
     }
 
@@ -82,10 +184,20 @@ impl Snake {
         self.body.front().unwrap()
     }
 
+    pub fn tail(&self) -> &Point {
+        self.body.back().unwrap()
+    }
+
     pub fn body(&self) -> impl IntoIterator<Item = &Point> {
         &self.body
     }
 
+    /// Whether a part of the snake is on `point`.
+    pub fn contains(&self, point: &Point) -> bool {
+        self.occupancy.contains(point)
+    }
+
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         self.body.len()
     }
@@ -94,6 +206,12 @@ impl Snake {
         self.direction
     }
 
+    /// Makes the snake come back through the opposite edge when leaving a
+    /// `(width, height)` board, or not if `None`.
+    pub fn set_wrap(&mut self, wrap: Option<(usize, usize)>) {
+        self.wrap = wrap;
+    }
+
     pub fn is_dead(&self) -> bool {
         self.is_dead
     }
@@ -102,33 +220,41 @@ impl Snake {
         self.is_growing = true;
     }
 
-    pub fn is_growing(&mut self) -> bool {
+    pub fn is_growing(&self) -> bool {
         self.is_growing
     }
 
+    /// Cell the head moves to on the next [`step`](Self::step).
+    pub fn next_head(&self) -> Point {
+        let new_head = self.head().moved(self.direction);
+
+        match self.wrap {
+            Some((width, height)) => new_head.wrapped(width, height),
+            None => new_head,
+        }
+    }
+
+    /// Moves the snake one cell forward, growing it if an apple was eaten.
     pub fn step(&mut self) {
-        #[rustfmt::skip]
-        let new_head = match self.direction {
-            Direction::Up =>    Point::new(self.head().x    , self.head().y + 1),
-            Direction::Right => Point::new(self.head().x + 1, self.head().y    ),
-            Direction::Down =>  Point::new(self.head().x    , self.head().y - 1),
-            Direction::Left =>  Point::new(self.head().x - 1, self.head().y    ),
-        };
-
-        if self.body.iter().rev().skip(1).any(|p| p == &new_head) {
+        let new_head = self.next_head();
+
+        // The tail moves out of the way, unless the snake grows
+        if self.contains(&new_head) && (self.is_growing || &new_head != self.tail()) {
             self.is_dead = true;
             return;
         }
 
         self.body.push_front(new_head);
+        self.occupancy.insert(new_head);
 
         if self.is_growing {
             self.is_growing = false;
-        } else {
-            self.body.pop_back();
+        } else if let Some(tail) = self.body.pop_back() {
+            self.occupancy.remove(&tail);
         }
     }
 
+    /// Turns the snake, unless `direction` is the opposite of its heading.
     pub fn turn(&mut self, direction: Direction) {
         let d1 = self.direction as i32;
         let d2 = direction as i32;
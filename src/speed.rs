@@ -0,0 +1,118 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Preset tick rates.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+    Insane,
+}
+
+impl Difficulty {
+    pub const VARIANTS: [Difficulty; 4] = [
+        Difficulty::Slow,
+        Difficulty::Normal,
+        Difficulty::Fast,
+        Difficulty::Insane,
+    ];
+
+    /// Ticks per second.
+    #[rustfmt::skip]
+    pub fn frame_rate(self) -> f64 {
+        match self {
+            Difficulty::Slow   => 6.0,
+            Difficulty::Normal => 10.0,
+            Difficulty::Fast   => 15.0,
+            Difficulty::Insane => 25.0,
+        }
+    }
+
+    #[rustfmt::skip]
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Slow   => "slow",
+            Difficulty::Normal => "normal",
+            Difficulty::Fast   => "fast",
+            Difficulty::Insane => "insane",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::VARIANTS
+            .into_iter()
+            .find(|difficulty| difficulty.name() == s)
+            .ok_or_else(|| format!("unknown difficulty '{s}'"))
+    }
+}
+
+/// Tick rate of a game as its score grows.
+///
+/// Starting from `base` ticks per second, the game speeds up by `increase`
+/// every `every` apples, up to `max`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Speed {
+    pub base: f64,
+    pub increase: f64,
+    pub every: usize,
+    pub max: f64,
+}
+
+impl Speed {
+    /// Ticks at `frame_rate` whatever the score.
+    pub fn constant(frame_rate: f64) -> Self {
+        Self {
+            base: frame_rate,
+            increase: 0.0,
+            every: 1,
+            max: frame_rate,
+        }
+    }
+
+    /// Starts at `frame_rate` and speeds up by one tick per second every
+    /// other apple, up to three times as fast.
+    pub fn progressive(frame_rate: f64) -> Self {
+        Self {
+            base: frame_rate,
+            increase: 1.0,
+            every: 2,
+            max: frame_rate * 3.0,
+        }
+    }
+
+    /// Slowest tick rate, whatever the schedule says.
+    pub const MIN_FRAME_RATE: f64 = 0.1;
+
+    /// Ticks per second at `score`, at least
+    /// [`MIN_FRAME_RATE`](Self::MIN_FRAME_RATE).
+    pub fn frame_rate(&self, score: usize) -> f64 {
+        let steps = (score / self.every.max(1)) as f64;
+        (self.base + self.increase * steps)
+            .min(self.max.max(self.base))
+            .max(Self::MIN_FRAME_RATE)
+    }
+}
+
+impl Default for Speed {
+    fn default() -> Self {
+        Difficulty::default().into()
+    }
+}
+
+impl From<Difficulty> for Speed {
+    fn from(value: Difficulty) -> Self {
+        Self::constant(value.frame_rate())
+    }
+}
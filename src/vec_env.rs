@@ -0,0 +1,254 @@
+use std::fmt;
+use std::mem;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use rl::burn::tensor::TensorData;
+use rl::env::Environment;
+
+/// Environments stepped in lockstep, so that a model rates all their states
+/// in one forward pass.
+///
+/// Finished episodes are reset right away: the states always hold the
+/// current observation of every environment.
+///
+/// With more than one thread, the environments are split into chunks handed
+/// to long-lived worker threads on every step, a game step being far too
+/// cheap to pay for spawning threads.
+#[derive(Debug)]
+pub struct VecEnv<E: Environment> {
+    envs: Vec<E>,
+    states: Vec<E::State>,
+    workers: Vec<Worker<E>>,
+}
+
+/// What happened to every environment on a [`VecEnv::step`].
+#[derive(Debug, Clone)]
+pub struct VecStep<S> {
+    pub rewards: Vec<f32>,
+    /// Whether the episode ended, the environment having been reset since.
+    pub dones: Vec<bool>,
+    /// Last state of the episodes that ended while still having one, as
+    /// truncated ones do.
+    pub final_states: Vec<Option<S>>,
+}
+
+/// What a step did to one environment: its reward, whether its episode
+/// ended and its final state.
+type Outcome<S> = (f32, bool, Option<S>);
+
+/// Environments, with their states, handed to a worker.
+struct Chunk<E: Environment> {
+    envs: Vec<E>,
+    states: Vec<E::State>,
+}
+
+/// A chunk with the actions to step it with.
+type Job<E> = (Chunk<E>, Vec<<E as Environment>::Action>);
+
+/// A stepped chunk with the outcome of every step.
+type Done<E> = (Chunk<E>, Vec<Outcome<<E as Environment>::State>>);
+
+/// A thread stepping the chunks it is sent.
+struct Worker<E: Environment> {
+    jobs: Sender<Job<E>>,
+    done: Receiver<Done<E>>,
+}
+
+impl<E: Environment> fmt::Debug for Worker<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Worker").finish_non_exhaustive()
+    }
+}
+
+impl<E> Worker<E>
+where
+    E: Environment + Send + 'static,
+    E::State: Send + 'static,
+    E::Action: Send + 'static,
+{
+    /// Starts the thread, which stops once the worker is dropped.
+    fn spawn() -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<Job<E>>();
+        let (done_sender, done) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (mut chunk, actions) in job_receiver {
+                let outcomes = chunk
+                    .envs
+                    .iter_mut()
+                    .zip(&mut chunk.states)
+                    .zip(actions)
+                    .map(|((env, state), action)| step_one(env, state, action))
+                    .collect();
+
+                if done_sender.send((chunk, outcomes)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { jobs, done }
+    }
+}
+
+impl<E> VecEnv<E>
+where
+    E: Environment + Send + 'static,
+    E::State: Send + 'static,
+    E::Action: Send + 'static,
+{
+    /// Resets `envs` to start their first episode.
+    pub fn new(mut envs: Vec<E>) -> Self {
+        let states = envs.iter_mut().map(Environment::reset).collect();
+
+        Self {
+            envs,
+            states,
+            workers: Vec::new(),
+        }
+    }
+
+    /// Splits the environments over `threads` threads when stepping.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.workers = match threads {
+            0 | 1 => Vec::new(),
+            threads => (0..threads).map(|_| Worker::spawn()).collect(),
+        };
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    pub fn envs(&self) -> &[E] {
+        &self.envs
+    }
+
+    pub fn envs_mut(&mut self) -> &mut [E] {
+        &mut self.envs
+    }
+
+    /// Current state of every environment.
+    pub fn states(&self) -> &[E::State] {
+        &self.states
+    }
+
+    /// Current states as one `[len, observation size]` batch of `f32`.
+    pub fn observations(&self) -> TensorData {
+        let mut values = Vec::new();
+        let mut size = 0;
+
+        for state in &self.states {
+            let data: TensorData = state.clone().into();
+            size = data.num_elements();
+            values.extend(
+                data.convert::<f32>()
+                    .to_vec::<f32>()
+                    .expect("observation converted to f32"),
+            );
+        }
+
+        TensorData::new(values, [self.states.len(), size])
+    }
+
+    /// Steps every environment with the action at the same index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not one action per environment.
+    pub fn step(&mut self, actions: Vec<E::Action>) -> VecStep<E::State> {
+        assert_eq!(
+            actions.len(),
+            self.envs.len(),
+            "expected one action per environment",
+        );
+
+        let outcomes: Vec<_> = if self.workers.is_empty() {
+            self.envs
+                .iter_mut()
+                .zip(&mut self.states)
+                .zip(actions)
+                .map(|((env, state), action)| step_one(env, state, action))
+                .collect()
+        } else {
+            self.step_on_workers(actions)
+        };
+
+        let mut step = VecStep {
+            rewards: Vec::with_capacity(outcomes.len()),
+            dones: Vec::with_capacity(outcomes.len()),
+            final_states: Vec::with_capacity(outcomes.len()),
+        };
+        for (reward, done, final_state) in outcomes {
+            step.rewards.push(reward);
+            step.dones.push(done);
+            step.final_states.push(final_state);
+        }
+        step
+    }
+
+    /// Steps consecutive chunks of the environments on the workers, then
+    /// puts them back in order.
+    fn step_on_workers(&mut self, actions: Vec<E::Action>) -> Vec<Outcome<E::State>> {
+        let len = self.envs.len();
+        let chunk_size = len.div_ceil(self.workers.len()).max(1);
+        let mut envs = mem::take(&mut self.envs).into_iter();
+        let mut states = mem::take(&mut self.states).into_iter();
+        self.envs.reserve(len);
+        self.states.reserve(len);
+        let mut actions = actions.into_iter();
+
+        let mut busy = 0;
+        for worker in &self.workers {
+            let chunk = Chunk {
+                envs: envs.by_ref().take(chunk_size).collect(),
+                states: states.by_ref().take(chunk_size).collect(),
+            };
+            if chunk.envs.is_empty() {
+                break;
+            }
+
+            let actions = actions.by_ref().take(chunk.envs.len()).collect();
+            worker
+                .jobs
+                .send((chunk, actions))
+                .expect("environment thread panicked");
+            busy += 1;
+        }
+
+        let mut outcomes = Vec::with_capacity(len);
+        for worker in &self.workers[..busy] {
+            let (chunk, chunk_outcomes) = worker.done.recv().expect("environment thread panicked");
+            self.envs.extend(chunk.envs);
+            self.states.extend(chunk.states);
+            outcomes.extend(chunk_outcomes);
+        }
+        outcomes
+    }
+}
+
+/// Steps `env`, resetting it if the episode ended.
+fn step_one<E: Environment>(
+    env: &mut E,
+    state: &mut E::State,
+    action: E::Action,
+) -> Outcome<E::State> {
+    let (next_state, reward) = env.step(action);
+
+    match next_state {
+        Some(next_state) if env.is_active() => {
+            *state = next_state;
+            (reward, false, None)
+        }
+        final_state => {
+            *state = env.reset();
+            (reward, true, final_state)
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::borrow::BorrowMut;
+use std::fmt::Debug;
+use std::io;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    prelude::*,
+    widgets::{Paragraph, Widget},
+};
+use rl::burn::tensor::backend::AutodiffBackend;
+use snake::env::Shapes;
+use snake::Game;
+
+use crate::model::LinearQNet;
+
+/// Watches `model` play `env` greedily in the terminal at `frame_rate`
+/// ticks per second, its rating of every action on the side.
+///
+/// Space pauses, r restarts and q quits.
+pub fn watch<B, E, T>(
+    model: &LinearQNet<B>,
+    mut env: E,
+    frame_rate: f64,
+    device: &B::Device,
+    mut terminal: Terminal<T>,
+) -> io::Result<()>
+where
+    B: AutodiffBackend,
+    E: Shapes + BorrowMut<Game>,
+    E::Action: Debug,
+    T: Backend,
+{
+    model.check_shapes(&env, device);
+
+    let actions: Vec<String> = env
+        .actions()
+        .iter()
+        .map(|action| format!("{action:?}"))
+        .collect();
+
+    let mut state = env.reset();
+    let mut is_playing = true;
+
+    'watch: loop {
+        let q_values = model.q_values(state.clone(), device);
+
+        let panel = Panel {
+            game: env.borrow(),
+            actions: &actions,
+            q_values: &q_values,
+            is_playing,
+        };
+        terminal.draw(|frame| frame.render_widget(&panel, frame.area()))?;
+
+        let now = Instant::now();
+        let timeout = Duration::from_secs_f64(1.0 / frame_rate);
+        let mut elapsed = now.elapsed();
+
+        while elapsed < timeout {
+            if event::poll(timeout - elapsed)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        match key.code {
+                            KeyCode::Char('q') | KeyCode::Esc => break 'watch,
+                            KeyCode::Char(' ') => is_playing = !is_playing,
+                            KeyCode::Char('r') => {
+                                state = env.reset();
+                                continue 'watch;
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+            }
+            elapsed = now.elapsed();
+        }
+
+        if is_playing && env.is_active() {
+            let action = model.greedy_action::<E>(state.clone(), device);
+            if let (Some(next_state), _) = env.step(action) {
+                state = next_state;
+            }
+        }
+    }
+
+    // Reset terminal cursor at the end of viewport
+    let area = terminal.get_frame().area();
+    terminal.set_cursor_position((0, area.height + area.y + 1))?;
+
+    Ok(())
+}
+
+struct Panel<'a> {
+    game: &'a Game,
+    actions: &'a [String],
+    q_values: &'a [f32],
+    is_playing: bool,
+}
+
+impl Widget for &Panel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let [board, status] = Layout::horizontal([
+            Constraint::Length((self.game.width() * 2 + 2) as u16),
+            Constraint::Min(0),
+        ])
+        .areas(area);
+
+        self.game.render(board, buf);
+
+        let best =
+            (0..self.q_values.len()).max_by(|&a, &b| self.q_values[a].total_cmp(&self.q_values[b]));
+
+        let mut lines = vec![
+            Line::from(format!(" Score {}", self.game.score())),
+            Line::from(if self.is_playing {
+                " Playing"
+            } else {
+                " Paused"
+            }),
+            Line::from(""),
+            Line::from(" Q-values"),
+        ];
+        for (i, (action, q_value)) in self.actions.iter().zip(self.q_values).enumerate() {
+            let line = Line::from(format!(" {action:<9}{q_value:>8.3}"));
+            lines.push(if Some(i) == best { line.bold() } else { line });
+        }
+        lines.extend([
+            Line::from(""),
+            Line::from(" space  play/pause").dark_gray(),
+            Line::from(" r      restart").dark_gray(),
+            Line::from(" q      quit").dark_gray(),
+        ]);
+
+        Paragraph::new(lines).render(status, buf);
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use ::snake::{Direction, Game, Level, Point};
+
+const SEEDS: u64 = 12_000;
+
+/// Chi-squared statistic of `counts` against a uniform distribution over
+/// `cells` cells.
+fn chi_squared(counts: &HashMap<Point, u64>, cells: usize) -> f64 {
+    let expected = SEEDS as f64 / cells as f64;
+    counts
+        .values()
+        .map(|&count| (count as f64 - expected).powi(2) / expected)
+        .sum()
+}
+
+/// Where the first random apple lands over many seeds, after `steps` steps.
+fn apple_counts(level: &str, steps: usize) -> HashMap<Point, u64> {
+    let level: Level = level.parse().unwrap();
+    let mut counts = HashMap::new();
+
+    for seed in 0..SEEDS {
+        let mut game = Game::from_level(level.clone(), seed);
+        for _ in 0..steps {
+            game.step();
+        }
+
+        let apple = *game.apple().position();
+        assert!(!game.snake().body().into_iter().any(|p| p == &apple));
+        assert!(!game.level().walls.contains(&apple));
+        *counts.entry(apple).or_default() += 1;
+    }
+
+    counts
+}
+
+// Critical value of the chi-squared distribution with 11 degrees of freedom
+// at p = 0.001, the seeds being fixed so that the test is not flaky
+const CRITICAL_11: f64 = 31.26;
+
+#[test]
+fn first_apple_is_uniform() {
+    let counts = apple_counts("length 3\n.....\n..>..\n.....\n", 0);
+
+    assert_eq!(counts.len(), 12);
+    assert!(chi_squared(&counts, 12) < CRITICAL_11);
+}
+
+#[test]
+fn apple_after_moving_is_uniform() {
+    let counts = apple_counts("length 3\n.....\n..>@.\n.....\n", 1);
+
+    assert_eq!(counts.len(), 12);
+    // The cell the tail left is free again
+    assert!(counts.contains_key(&Point::new(0, 1)));
+    assert!(chi_squared(&counts, 12) < CRITICAL_11);
+}
+
+// Critical value with 3 degrees of freedom at p = 0.001
+const CRITICAL_3: f64 = 16.27;
+
+#[test]
+fn apple_avoids_walls() {
+    let counts = apple_counts("length 3\n#.###\n..>..\n####.\n", 0);
+
+    assert_eq!(counts.len(), 4);
+    assert!(chi_squared(&counts, 4) < CRITICAL_3);
+}
+
+#[test]
+fn single_free_cell_is_chosen() {
+    let level: Level = "length 3\n.<..\n".parse().unwrap();
+
+    for seed in 0..100 {
+        let game = Game::from_level(level.clone(), seed);
+        assert_eq!(game.apple().position(), &Point::new(0, 0));
+    }
+}
+
+#[test]
+fn apples_do_not_depend_on_wall_order() {
+    // Every parse fills the set of walls with its own hasher, so the same
+    // seed must not rely on their iteration order
+    let text = Level::builtin("pillars").unwrap().to_string();
+    let apples = || {
+        let mut level: Level = text.parse().unwrap();
+        level.apples.clear();
+
+        (0..100)
+            .map(|seed| *Game::from_level(level.clone(), seed).apple().position())
+            .collect::<Vec<_>>()
+    };
+
+    let first = apples();
+    for _ in 0..10 {
+        assert_eq!(apples(), first);
+    }
+}
+
+#[test]
+fn apples_avoid_a_snake_crossing_itself() {
+    use Direction::*;
+
+    // Growing into its own tail, the snake may cover a cell twice and must
+    // not give it back when its tail first leaves it
+    let level: Level = "length 2\n..@@\n..>@\n".parse().unwrap();
+
+    for seed in 0..200 {
+        let mut game = Game::from_level(level.clone(), seed);
+        for direction in [Up, Right, Down, Left, Left, Up, Left, Down, Right, Up] {
+            game.set_direction(direction);
+            game.step();
+            if !game.is_running() {
+                break;
+            }
+
+            let apple = game.apple().position();
+            assert!(
+                !game.snake().contains(apple),
+                "apple on the snake with seed {seed}",
+            );
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use ::snake::{Game, HighScores, Level, Rules};
+
+/// Path of a table only used by `test`, removed first.
+fn table_path(test: &str) -> PathBuf {
+    let path = env::temp_dir()
+        .join(format!("snake-test-{}", std::process::id()))
+        .join(format!("{test}.tsv"));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn scores<'a>(high_scores: &'a HighScores, board: &'a str) -> Vec<(usize, &'a str)> {
+    high_scores
+        .top(board)
+        .map(|entry| (entry.score, entry.name.as_str()))
+        .collect()
+}
+
+#[test]
+fn scores_are_kept_highest_first() {
+    let mut high_scores = HighScores::default();
+    high_scores.insert("a", 3, "first");
+    high_scores.insert("a", 5, "second");
+    high_scores.insert("b", 9, "other");
+    high_scores.insert("a", 3, "third");
+
+    assert_eq!(
+        scores(&high_scores, "a"),
+        [(5, "second"), (3, "third"), (3, "first")]
+    );
+    assert_eq!(scores(&high_scores, "b"), [(9, "other")]);
+}
+
+#[test]
+fn tables_keep_the_best_scores() {
+    let mut high_scores = HighScores::default();
+    for score in 1..=HighScores::LEN {
+        assert!(high_scores.is_record("a", score));
+        high_scores.insert("a", score, "player");
+    }
+
+    assert!(!high_scores.is_record("a", 1));
+    assert!(high_scores.is_record("a", 2));
+    assert!(high_scores.is_record("b", 1));
+    assert!(!high_scores.is_record("b", 0));
+
+    high_scores.insert("a", 20, "player");
+    let kept = scores(&high_scores, "a");
+    assert_eq!(kept.len(), HighScores::LEN);
+    assert_eq!(kept.first(), Some(&(20, "player")));
+    assert_eq!(kept.last(), Some(&(2, "player")));
+}
+
+#[test]
+fn tables_round_trip() {
+    let path = table_path("round_trip");
+    let mut high_scores = HighScores::load(&path).unwrap();
+    high_scores.insert("17x15 empty", 12, "ann");
+    high_scores.insert("17x15 empty", 4, "tab\tname");
+    high_scores.insert("8x8 box wrap-around", 7, "bob");
+    high_scores.save().unwrap();
+
+    let loaded = HighScores::load(&path).unwrap();
+    for board in ["17x15 empty", "8x8 box wrap-around"] {
+        assert_eq!(scores(&loaded, board), scores(&high_scores, board));
+    }
+    assert_eq!(scores(&loaded, "17x15 empty")[1], (4, "tab name"));
+}
+
+#[test]
+fn invalid_tables_are_rejected() {
+    let path = table_path("invalid");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "17x15 empty\tmany\tann\n").unwrap();
+
+    assert!(HighScores::load(&path).is_err());
+}
+
+#[test]
+fn boards_are_told_apart_by_layout() {
+    let box_board = HighScores::board(&Game::from_level(Level::builtin("box").unwrap(), 1));
+
+    let mut level = Level::builtin("cross").unwrap();
+    level.name = String::from("box");
+    let renamed = HighScores::board(&Game::from_level(level, 1));
+    assert_ne!(renamed, box_board);
+
+    let mut game = Game::from_level(Level::builtin("box").unwrap(), 2);
+    assert_eq!(HighScores::board(&game), box_board);
+    game.set_rules(Rules { wrap_around: true });
+    assert_ne!(HighScores::board(&game), box_board);
+}
+
+#[test]
+fn tabs_in_level_names_do_not_split_fields() {
+    let level: Level = "name tab\tbed\nlength 2\n.>...\n".parse().unwrap();
+    let board = HighScores::board(&Game::from_level(level, 1));
+    assert!(!board.contains('\t'));
+
+    let path = table_path("tabs");
+    let mut high_scores = HighScores::load(&path).unwrap();
+    high_scores.insert(&board, 3, "ann");
+    high_scores.save().unwrap();
+
+    let loaded = HighScores::load(&path).unwrap();
+    assert_eq!(scores(&loaded, &board), [(3, "ann")]);
+}
@@ -0,0 +1,55 @@
+use ::snake::{Direction, Game, Point};
+
+/// Game with the snake at (3, 5) heading right, queues `directions` and
+/// returns the head after `steps` steps.
+fn head_after(directions: &[Direction], steps: usize) -> Point {
+    let mut game = Game::with_seed(10, 10, 1);
+    assert_eq!(*game.snake().head(), Point::new(3, 5));
+
+    for &direction in directions {
+        game.queue_direction(direction);
+    }
+    for _ in 0..steps {
+        game.step();
+    }
+
+    *game.snake().head()
+}
+
+#[test]
+fn turns_within_a_tick_are_all_taken() {
+    let head = head_after(&[Direction::Up, Direction::Left], 2);
+
+    assert_eq!(head, Point::new(2, 6));
+}
+
+#[test]
+fn reversals_are_dropped() {
+    assert_eq!(head_after(&[Direction::Left], 1), Point::new(4, 5));
+
+    let head = head_after(&[Direction::Up, Direction::Down, Direction::Left], 2);
+    assert_eq!(head, Point::new(2, 6));
+}
+
+#[test]
+fn duplicates_are_dropped() {
+    assert_eq!(head_after(&[Direction::Right], 1), Point::new(4, 5));
+
+    let head = head_after(&[Direction::Up, Direction::Up, Direction::Left], 2);
+    assert_eq!(head, Point::new(2, 6));
+}
+
+#[test]
+fn turns_beyond_the_buffer_are_dropped() {
+    let turns = [
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+        Direction::Right,
+    ];
+    assert_eq!(Game::INPUT_BUFFER_SIZE, turns.len() - 1);
+
+    // The last turn is dropped, the snake keeps heading down
+    let head = head_after(&turns, turns.len());
+    assert_eq!(head, Point::new(2, 4));
+}
@@ -0,0 +1,99 @@
+use ::snake::{Direction, Level, Point};
+
+fn round_trip(level: &Level) {
+    let text = level.to_string();
+    let parsed: Level = text.parse().unwrap();
+
+    assert_eq!(&parsed, level);
+    assert_eq!(parsed.to_string(), text);
+}
+
+#[test]
+fn builtin_levels_round_trip() {
+    for (name, _) in ::snake::level::BUILTIN {
+        let level = Level::builtin(name).unwrap();
+        assert_eq!(level.name, name);
+        round_trip(&level);
+    }
+}
+
+#[test]
+fn empty_level_round_trips() {
+    round_trip(&Level::empty(17, 15));
+}
+
+#[test]
+fn level_is_parsed() {
+    let level: Level = "\
+; A small level
+name small
+length 2
+#####
+#.@.#
+#.^.#
+#...#
+"
+    .parse()
+    .unwrap();
+
+    assert_eq!(level.name, "small");
+    assert_eq!((level.width, level.height), (5, 4));
+    assert_eq!(level.head, Point::new(2, 1));
+    assert_eq!(level.direction, Direction::Up);
+    assert_eq!(level.length, 2);
+    assert_eq!(level.apples, [Point::new(2, 2)]);
+    assert_eq!(level.walls.len(), 5 + 3 * 2);
+    round_trip(&level);
+}
+
+#[test]
+fn only_the_empty_level_is_an_empty_board() {
+    assert!(Level::empty(17, 15).is_empty_board());
+
+    let mut level = Level::empty(17, 15);
+    level.name = String::from("custom");
+    assert!(!level.is_empty_board());
+
+    // Narrower than an empty board can be
+    let level: Level = "length 2\n.>@..\n".parse().unwrap();
+    assert!(!level.is_empty_board());
+}
+
+#[test]
+fn snake_off_the_board_is_rejected() {
+    assert!("length 4\n..>...\n".parse::<Level>().is_err());
+    assert!("length 2\n^..\n...\n".parse::<Level>().is_ok());
+    assert!("length 3\n^..\n...\n".parse::<Level>().is_err());
+}
+
+#[test]
+fn snake_in_a_wall_is_rejected() {
+    assert!("length 3\n.#>..\n".parse::<Level>().is_err());
+}
+
+#[test]
+fn apple_on_the_snake_is_rejected() {
+    assert!("length 3\n.@>..\n".parse::<Level>().is_err());
+}
+
+#[test]
+fn size_mismatch_is_rejected() {
+    assert!("length 2\n.>...\n....\n".parse::<Level>().is_err());
+}
+
+#[test]
+fn invalid_levels_are_rejected() {
+    for text in [
+        "",
+        "length 2\n",
+        "length 2\n.....\n",
+        "length 2\n.>.<.\n",
+        "length 0\n.>...\n",
+        "length 1000000000000\n.>...\n",
+        "length 2\n.>.x.\n",
+        "size 5\n.>...\n",
+        "length 2\n.>...\nname late\n",
+    ] {
+        assert!(text.parse::<Level>().is_err(), "accepted {text:?}");
+    }
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "rl")]
+
+use ::snake::observation::{self, Danger, Grid, Observation, Window};
+use ::snake::{Direction, Game, Level, Rules};
+
+fn game(level: &str) -> Game {
+    Game::from_level(level.parse::<Level>().unwrap(), 1)
+}
+
+/// Indices of the values set in an encoding.
+fn ones(values: &[f32]) -> Vec<usize> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, &value)| value != 0.0)
+        .inspect(|(_, &value)| assert_eq!(value, 1.0))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[test]
+fn encodings_have_their_size() {
+    let mut games = vec![Game::with_seed(17, 15, 3), game("length 2\n.#...\n.>@..\n")];
+    let mut wrapped = Game::from_level(Level::builtin("tunnels").unwrap(), 5);
+    wrapped.set_rules(Rules { wrap_around: true });
+    games.push(wrapped);
+
+    for name in observation::NAMES {
+        let observation = observation::by_name(name).unwrap();
+        for game in &games {
+            assert_eq!(
+                observation.encode(game).len(),
+                observation.size(game),
+                "{name}",
+            );
+        }
+    }
+}
+
+#[test]
+fn danger_is_encoded() {
+    // Heading right with a wall up, to the left of the snake
+    let game = game("length 2\n.#...\n.>@..\n.....\n");
+
+    #[rustfmt::skip]
+    let expected = [
+        0.0, 0.0, 1.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+    ];
+    assert_eq!(Danger.encode(&game), expected);
+}
+
+#[test]
+fn growing_into_the_tail_is_a_danger() {
+    let mut game = game("length 2\n..@@\n..>@\n");
+    for direction in [Direction::Up, Direction::Right, Direction::Down] {
+        game.set_direction(direction);
+        game.step();
+    }
+
+    // Heading down in the corner, with the tail on its right staying put
+    assert!(game.snake().is_growing());
+    assert_eq!(Danger.encode(&game)[..3], [1.0, 1.0, 1.0]);
+}
+
+#[test]
+fn grid_is_encoded() {
+    let game = game("length 2\n.#...\n.>@..\n.....\n");
+    let cells = 5 * 3;
+
+    // Head at (1, 1), body at (0, 1), apple at (2, 1) and wall at (1, 2)
+    assert_eq!(
+        ones(&Grid.encode(&game)),
+        [6, cells + 5, 2 * cells + 7, 3 * cells + 11]
+    );
+}
+
+#[test]
+fn window_is_encoded() {
+    let game = game("length 2\n.#...\n.>@..\n.....\n");
+    let window = Window { radius: 1 };
+    let cells = 3 * 3;
+
+    // Facing right, the rows run left to right and the columns top to bottom
+    assert_eq!(
+        ones(&window.encode(&game)),
+        [1, 4, cells + 7, 2 * cells + 3]
+    );
+}
+
+#[test]
+fn window_wraps_around() {
+    let mut game = game("length 1\n.....\n>@..#\n.....\n");
+    let window = Window { radius: 1 };
+    let cells = 3 * 3;
+
+    // The row behind the head is off the board
+    assert_eq!(
+        ones(&window.encode(&game)),
+        [4, cells + 7, 2 * cells, 2 * cells + 1, 2 * cells + 2]
+    );
+
+    // Unless it wraps around to the right edge, where only the wall blocks
+    game.set_rules(Rules { wrap_around: true });
+    assert_eq!(ones(&window.encode(&game)), [4, cells + 7, 2 * cells + 1]);
+}
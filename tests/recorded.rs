@@ -0,0 +1,29 @@
+#![cfg(feature = "rl")]
+
+use ::snake::env::Recorded;
+use ::snake::{Direction, Game, Level};
+use rl::env::Environment;
+
+#[test]
+fn transitions_of_the_episode_are_kept() {
+    let level: Level = "length 2\n.>.@#\n".parse().unwrap();
+    let mut env = Recorded::new(Game::from_level(level, 1));
+
+    let first = env.reset();
+    for _ in 0..3 {
+        env.step(Direction::Right);
+    }
+
+    let transitions = env.transitions();
+    assert_eq!(transitions.len(), 3);
+    assert_eq!(transitions[0].0, first);
+    assert_eq!(transitions[1].0, transitions[0].3.clone().unwrap());
+    assert!(transitions
+        .iter()
+        .all(|(_, action, ..)| *action == Direction::Right));
+    // Crashing into the wall ends the episode
+    assert!(transitions[2].3.is_none());
+
+    env.reset();
+    assert!(env.transitions().is_empty());
+}
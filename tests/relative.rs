@@ -0,0 +1,31 @@
+#![cfg(feature = "rl")]
+
+use ::snake::env::{Relative, Turn};
+use ::snake::{Direction, Game};
+use rl::env::Environment;
+
+#[test]
+fn turns_are_relative_to_the_heading() {
+    for heading in Direction::VARIANTS {
+        assert_eq!(Turn::Straight.apply(heading), heading);
+        assert_eq!(Turn::Right.apply(heading), heading.clockwise());
+        assert_eq!(Turn::Left.apply(heading), heading.counter_clockwise());
+    }
+    assert_eq!(Turn::Right.apply(Direction::Up), Direction::Right);
+    assert_eq!(Turn::Left.apply(Direction::Up), Direction::Left);
+}
+
+#[test]
+fn random_actions_are_reseeded_on_reset() {
+    let mut played = Relative::new(Game::with_seed(8, 8, 1));
+    let mut fresh = Relative::new(Game::with_seed(8, 8, 1));
+    for _ in 0..5 {
+        played.random_action();
+    }
+
+    played.reset();
+    fresh.reset();
+
+    let actions = |env: &Relative| (0..20).map(|_| env.random_action()).collect::<Vec<_>>();
+    assert_eq!(actions(&played), actions(&fresh));
+}
@@ -0,0 +1,87 @@
+use ::snake::{Direction, Game, Level, Replay, Rules};
+
+fn round_trip(replay: &Replay) {
+    let text = replay.to_string();
+    let parsed: Replay = text.parse().unwrap();
+
+    assert_eq!(&parsed, replay);
+    assert_eq!(parsed.to_string(), text);
+}
+
+#[test]
+fn empty_board_round_trips() {
+    let mut game = Game::with_seed(8, 5, 42);
+    game.set_rules(Rules { wrap_around: true });
+    for direction in [Direction::Up, Direction::Up, Direction::Left] {
+        game.set_direction(direction);
+        game.step();
+    }
+
+    let replay = game.replay();
+    assert!(!replay.to_string().contains("level"));
+    round_trip(&replay);
+}
+
+#[test]
+fn level_round_trips() {
+    let level = Level::builtin("pillars").unwrap();
+    let mut game = Game::from_level(level, 7);
+    game.step();
+
+    let replay = game.replay();
+    assert!(replay.to_string().contains("\nlevel\n"));
+    round_trip(&replay);
+}
+
+#[test]
+fn narrow_level_round_trips() {
+    // Narrower than an empty board can be
+    let level: Level = "length 2\n.>@..\n.....\n.....\n".parse().unwrap();
+    let game = Game::from_level(level, 1);
+
+    round_trip(&game.replay());
+}
+
+#[test]
+fn replay_plays_the_game_again() {
+    let level = Level::builtin("box").unwrap();
+    let mut game = Game::from_level(level, 3);
+    for direction in [
+        Direction::Up,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ] {
+        game.set_direction(direction);
+        game.step();
+    }
+
+    let replay: Replay = game.replay().to_string().parse().unwrap();
+    let replayed = replay.game_at(replay.len());
+
+    assert_eq!(replayed.snake().head(), game.snake().head());
+    assert_eq!(replayed.apple(), game.apple());
+    assert_eq!(replayed.score(), game.score());
+}
+
+#[test]
+fn size_mismatch_is_rejected() {
+    let text =
+        "snake-replay 1\nsize 6 3\nrules\nseed 1\nscore 0\ninputs RR\nlevel\nlength 2\n.>@..\n";
+
+    assert!(text.parse::<Replay>().is_err());
+}
+
+#[test]
+fn invalid_replays_are_rejected() {
+    for text in [
+        "size 17 15\nseed 1\nscore 0\n",
+        "snake-replay 1\nseed 1\nscore 0\n",
+        "snake-replay 1\nsize 17 15\nscore 0\n",
+        "snake-replay 1\nsize 17 15\nseed 1\nscore 0\ninputs RX\n",
+        "snake-replay 1\nsize 17 15\nrules diagonal\nseed 1\nscore 0\n",
+        "snake-replay 1\nsize 2 2\nseed 1\nscore 0\n",
+    ] {
+        assert!(text.parse::<Replay>().is_err(), "accepted {text:?}");
+    }
+}
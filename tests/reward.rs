@@ -0,0 +1,61 @@
+#![cfg(feature = "rl")]
+
+use ::snake::reward::RewardConfig;
+use ::snake::{Direction, Game, Level};
+use rl::env::Environment;
+
+#[test]
+fn config_is_parsed() {
+    let text = "; Hurry\nstep -0.5\n\napple 2\nstarvation-steps 10\n";
+    let config: RewardConfig = text.parse().unwrap();
+
+    assert_eq!(
+        config,
+        RewardConfig {
+            step: -0.5,
+            apple: 2.0,
+            starvation_steps: 10,
+            ..RewardConfig::default()
+        }
+    );
+    assert_eq!(config.to_string().parse::<RewardConfig>(), Ok(config));
+}
+
+#[test]
+fn invalid_configs_are_rejected() {
+    for text in [
+        "step",
+        "step fast",
+        "speed 1",
+        "starvation-steps -1",
+        "apple NaN",
+        "death -inf",
+        "win inf",
+    ] {
+        assert!(text.parse::<RewardConfig>().is_err(), "accepted {text:?}");
+    }
+}
+
+#[test]
+fn components_are_reported() {
+    let level: Level = "length 2\n.>.@#\n".parse().unwrap();
+    let mut game = Game::from_level(level, 1);
+    game.set_rewards(RewardConfig {
+        step: -0.5,
+        apple: 2.0,
+        death: -3.0,
+        distance: 0.25,
+        ..RewardConfig::default()
+    });
+
+    // Closer to the apple, onto it, then into the wall
+    let rewards: Vec<f32> = (0..3)
+        .map(|_| Environment::step(&mut game, Direction::Right).1)
+        .collect();
+    assert_eq!(rewards, [-0.25, 1.5, -3.5]);
+
+    let report = game.report.take();
+    let components = RewardConfig::REPORT_KEYS.map(|key| report[key]);
+    assert_eq!(components, [-1.5, 2.0, -3.0, 0.0, 0.25, 0.0]);
+    assert_eq!(report["reward"], -2.25);
+}
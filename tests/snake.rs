@@ -0,0 +1,45 @@
+use ::snake::{Direction, Game, GameState, Level, Point};
+
+#[test]
+fn moving_onto_the_tail_is_safe() {
+    // Circling a 2x2 square, the head takes the cell the tail leaves
+    let level: Level = "length 4\n.....@\n...>..\n".parse().unwrap();
+    let mut game = Game::from_level(level, 1);
+
+    for direction in [Direction::Up, Direction::Left, Direction::Down] {
+        game.set_direction(direction);
+        game.step();
+    }
+
+    assert_eq!(game.state(), GameState::Running);
+    assert_eq!(game.snake().head(), &Point::new(2, 0));
+    assert_eq!(game.snake().body().into_iter().count(), 4);
+}
+
+#[test]
+fn moving_onto_the_tail_while_growing_is_deadly() {
+    // The last apple is eaten next to the tail, which then stays in place
+    let level: Level = "length 2\n..@@\n..>@\n".parse().unwrap();
+    let mut game = Game::from_level(level, 1);
+
+    for direction in [Direction::Up, Direction::Right, Direction::Down] {
+        game.set_direction(direction);
+        game.step();
+    }
+    assert_eq!(game.state(), GameState::Running);
+
+    game.set_direction(Direction::Left);
+    game.step();
+
+    assert_eq!(game.state(), GameState::Over);
+    let body: Vec<_> = game.snake().body().into_iter().copied().collect();
+    assert_eq!(
+        body,
+        [
+            Point::new(3, 0),
+            Point::new(3, 1),
+            Point::new(2, 1),
+            Point::new(2, 0)
+        ]
+    );
+}
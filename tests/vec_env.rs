@@ -0,0 +1,94 @@
+#![cfg(feature = "rl")]
+
+use ::snake::env::{EpisodeLimits, Shapes};
+use ::snake::vec_env::VecEnv;
+use ::snake::{Direction, Game};
+
+fn games(count: u64, width: usize, height: usize) -> Vec<Game> {
+    (0..count)
+        .map(|seed| Game::with_seed(width, height, seed))
+        .collect()
+}
+
+#[test]
+fn observations_are_batched() {
+    let envs = VecEnv::new(games(3, 17, 15));
+    let size = envs.envs()[0].observation_size();
+
+    assert_eq!(envs.observations().shape, [3, size]);
+}
+
+#[test]
+fn actions_go_to_their_environment() {
+    let mut envs = VecEnv::new(games(5, 17, 15)).with_threads(2);
+    let actions: Vec<_> = (0..5)
+        .map(|i| match i % 2 {
+            0 => Direction::Up,
+            _ => Direction::Down,
+        })
+        .collect();
+
+    envs.step(actions.clone());
+
+    for (env, action) in envs.envs().iter().zip(actions) {
+        assert_eq!(env.snake().direction(), action);
+    }
+}
+
+#[test]
+fn finished_episodes_are_reset() {
+    // Eats the apple, then runs into the edge on the third step
+    let mut envs = VecEnv::new(games(2, 6, 1)).with_threads(2);
+
+    for _ in 0..2 {
+        let step = envs.step(vec![Direction::Right; 2]);
+        assert_eq!(step.dones, [false, false]);
+    }
+
+    let step = envs.step(vec![Direction::Right; 2]);
+    assert_eq!(step.dones, [true, true]);
+    assert!(step.final_states.iter().all(Option::is_none));
+    for env in envs.envs() {
+        assert!(env.is_running());
+        assert!(env.replay().is_empty());
+    }
+}
+
+#[test]
+fn truncated_episodes_keep_their_final_state() {
+    let mut games = games(3, 17, 15);
+    for game in &mut games {
+        game.set_limits(EpisodeLimits {
+            max_steps: Some(1),
+            ..EpisodeLimits::default()
+        });
+    }
+    let mut envs = VecEnv::new(games).with_threads(2);
+
+    let step = envs.step(vec![Direction::Right; 3]);
+
+    assert_eq!(step.dones, [true; 3]);
+    assert!(step.final_states.iter().all(Option::is_some));
+}
+
+#[test]
+fn threads_step_like_one() {
+    let mut sequential = VecEnv::new(games(7, 8, 6));
+    let mut threaded = VecEnv::new(games(7, 8, 6)).with_threads(3);
+
+    for tick in 0..200 {
+        let actions: Vec<_> = (0..7)
+            .map(|i| Direction::from(((tick / 3 + i) % 4) as isize))
+            .collect();
+
+        let expected = sequential.step(actions.clone());
+        let step = threaded.step(actions);
+
+        assert_eq!(step.rewards, expected.rewards);
+        assert_eq!(step.dones, expected.dones);
+        assert_eq!(
+            threaded.observations().to_vec::<f32>().unwrap(),
+            sequential.observations().to_vec::<f32>().unwrap(),
+        );
+    }
+}
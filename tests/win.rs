@@ -0,0 +1,22 @@
+use ::snake::{Game, GameState, Level, Rules};
+
+#[test]
+fn filling_the_board_wins() {
+    // The apple is eaten on the first step, the next one spawns on the cell
+    // the tail left and eating it fills the board
+    let level: Level = "length 2\n.>@\n".parse().unwrap();
+    let mut game = Game::from_level(level, 0);
+    game.set_rules(Rules { wrap_around: true });
+
+    game.step();
+    assert_eq!(game.state(), GameState::Running);
+    assert_eq!(game.score(), 1);
+
+    game.step();
+    assert_eq!(game.state(), GameState::Won);
+    assert_eq!(game.score(), 2);
+
+    // A won game stays won
+    game.step();
+    assert_eq!(game.state(), GameState::Won);
+}